@@ -0,0 +1,92 @@
+use std::convert::Infallible;
+use std::fmt;
+use std::io;
+use crate::ParseError;
+
+/// Result type returned by the solutions
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Error returned when a puzzle input cannot be read, parsed or solved
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be read
+    Io(io::Error),
+    /// The input is malformed
+    Parse(ParseError),
+    /// The input is well-formed but has no answer
+    Solve(String),
+    /// A day's own error, raised on input line `line` if it's known, which callers can get back with
+    /// [`Error::day_error`]
+    Day { line: Option<usize>, source: Box<dyn std::error::Error + Send + Sync> }
+}
+
+impl Error {
+    pub fn solve(details: impl Into<String>) -> Error {
+        Error::Solve(details.into())
+    }
+
+    /// Wraps a day's own error `err`, without a line number
+    pub fn day(err: impl std::error::Error + Send + Sync + 'static) -> Error {
+        Error::Day { line: None, source: Box::new(err) }
+    }
+
+    /// Records the line number on parse errors and day errors, passing other errors through
+    pub fn at_line(self, line: usize) -> Error {
+        match self {
+            Error::Parse(err) => Error::Parse(err.at_line(line)),
+            Error::Day { line: None, source } => Error::Day { line: Some(line), source },
+            err => err
+        }
+    }
+
+    /// The day's own error, if this wraps one of type `E`
+    pub fn day_error<E>(&self) -> Option<&E>
+    where E: std::error::Error + 'static {
+        match self {
+            Error::Day { source, .. } => source.downcast_ref::<E>(),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "failed to read input: {err}"),
+            Error::Parse(err) => write!(f, "failed to parse input: {err}"),
+            Error::Solve(details) => write!(f, "failed to solve: {details}"),
+            Error::Day { line: Some(line), source } => write!(f, "failed to solve: line {line}: {source}"),
+            Error::Day { line: None, source } => write!(f, "failed to solve: {source}")
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Parse(err) => Some(err),
+            Error::Day { source, .. } => Some(source.as_ref()),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+// lets `?` be used on the results of infallible line sources like `str::lines`
+impl From<Infallible> for Error {
+    fn from(err: Infallible) -> Self {
+        match err {}
+    }
+}
@@ -0,0 +1,174 @@
+use std::convert::Infallible;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// Opens the file at `filename` for buffered reading
+pub fn open<P>(filename: P) -> io::Result<BufReader<File>>
+where P: AsRef<Path> {
+    let file = File::open(filename)?;
+    Ok(BufReader::new(file))
+}
+
+// from https://doc.rust-lang.org/rust-by-example/std_misc/file/read_lines.html
+/// Reads the lines of the file at `filename`
+pub fn read_lines<P>(filename: P) -> io::Result<io::Lines<BufReader<File>>>
+where P: AsRef<Path> {
+    Ok(open(filename)?.lines())
+}
+
+/// A line yielded by a line source, which may have failed to read
+///
+/// This lets the same grouping code run over `str::lines` (which can't fail) and `BufRead::lines` (which can).
+pub trait TryLine {
+    type Line: AsRef<str>;
+    type Error;
+
+    fn try_line(self) -> Result<Self::Line, Self::Error>;
+}
+
+impl<'a> TryLine for &'a str {
+    type Line = &'a str;
+    type Error = Infallible;
+
+    fn try_line(self) -> Result<Self::Line, Self::Error> {
+        Ok(self)
+    }
+}
+
+impl TryLine for io::Result<String> {
+    type Line = String;
+    type Error = io::Error;
+
+    fn try_line(self) -> Result<Self::Line, Self::Error> {
+        self
+    }
+}
+
+/// Groups `lines` into records separated by one or more blank lines
+pub fn records<I>(lines: I) -> Records<I::IntoIter>
+where I: IntoIterator, I::Item: TryLine {
+    Records { lines: lines.into_iter(), line_number: 0 }
+}
+
+/// Iterator over blank-line-separated records, see [`records`]
+pub struct Records<I> {
+    lines: I,
+    line_number: usize
+}
+
+/// Consecutive non-blank lines, starting at line `line_number` of the input
+#[derive(Debug, PartialEq, Eq)]
+pub struct Record<L> {
+    pub line_number: usize,
+    pub lines: Vec<L>
+}
+
+impl<L> Record<L> {
+    /// Iterates over the lines along with their line numbers
+    pub fn numbered(&self) -> impl Iterator<Item = (usize, &L)> {
+        (self.line_number..).zip(self.lines.iter())
+    }
+}
+
+impl<I> Iterator for Records<I>
+where I: Iterator, I::Item: TryLine {
+    type Item = Result<Record<<I::Item as TryLine>::Line>, <I::Item as TryLine>::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut lines = Vec::new();
+        for l in self.lines.by_ref() {
+            self.line_number += 1;
+            let line = match l.try_line() {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err))
+            };
+
+            if !line.as_ref().is_empty() {
+                lines.push(line);
+            } else if !lines.is_empty() {
+                return Some(Ok(Record { line_number: self.line_number - lines.len(), lines }));
+            }
+        }
+
+        if lines.is_empty() {
+            None
+        } else {
+            Some(Ok(Record { line_number: self.line_number + 1 - lines.len(), lines }))
+        }
+    }
+}
+
+/// Groups `lines` into consecutive chunks of `N` lines, ignoring an incomplete final chunk
+pub fn chunks<const N: usize, I>(lines: I) -> Chunks<I::IntoIter, N>
+where I: IntoIterator, I::Item: TryLine {
+    Chunks { lines: lines.into_iter() }
+}
+
+/// Iterator over fixed-size chunks of lines, see [`chunks`]
+pub struct Chunks<I, const N: usize> {
+    lines: I
+}
+
+impl<I, const N: usize> Iterator for Chunks<I, N>
+where I: Iterator, I::Item: TryLine {
+    type Item = Result<[<I::Item as TryLine>::Line; N], <I::Item as TryLine>::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = Vec::with_capacity(N);
+        while chunk.len() < N {
+            match self.lines.next()?.try_line() {
+                Ok(line) => chunk.push(line),
+                Err(err) => return Some(Err(err))
+            }
+        }
+
+        chunk.try_into().ok().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufRead;
+    use super::{chunks, records};
+
+    const EXAMPLE: &str = "\
+1
+2
+
+3
+
+
+4
+5
+6
+";
+
+    #[test]
+    fn records_from_str() {
+        let groups = records(EXAMPLE.lines()).map(|r| r.unwrap().lines).collect::<Vec<_>>();
+
+        assert_eq!(groups, vec![vec!["1", "2"], vec!["3"], vec!["4", "5", "6"]])
+    }
+
+    #[test]
+    fn records_from_reader() {
+        let groups = records(EXAMPLE.as_bytes().lines()).map(|r| r.unwrap().lines).collect::<Vec<_>>();
+
+        assert_eq!(groups, vec![vec!["1", "2"], vec!["3"], vec!["4", "5", "6"]])
+    }
+
+    #[test]
+    fn records_line_numbers() {
+        let line_numbers = records(EXAMPLE.trim_end().lines()).map(|r| r.unwrap().line_number).collect::<Vec<_>>();
+
+        assert_eq!(line_numbers, vec![1, 4, 7])
+    }
+
+    #[test]
+    fn chunks_ignore_incomplete_final_chunk() {
+        let groups = chunks::<3, _>("a\nb\nc\nd\ne".lines()).collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(groups, vec![["a", "b", "c"]])
+    }
+}
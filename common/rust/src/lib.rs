@@ -0,0 +1,11 @@
+//! Helpers shared by the Advent of Code 2022 solutions
+
+mod error;
+mod input;
+mod parse_error;
+mod solution;
+
+pub use error::{Error, Result};
+pub use input::{chunks, open, read_lines, records, Chunks, Record, Records, TryLine};
+pub use parse_error::ParseError;
+pub use solution::Solution;
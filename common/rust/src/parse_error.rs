@@ -0,0 +1,146 @@
+use std::fmt;
+use std::ops::Range;
+
+/// Describes where and why a line of input failed to parse
+///
+/// Renders with a caret under the offending text, e.g.
+///
+/// ```text
+/// day 2, line 3, column 1: expected one of `A`, `B` or `C`, found `D`
+///   |
+/// 3 | D Y
+///   | ^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Day of the puzzle being parsed, if the input is a puzzle input
+    pub day: Option<u8>,
+    /// Line number, starting at 1, if known
+    pub line: Option<usize>,
+    /// Byte range of the offending text within `text`
+    pub span: Range<usize>,
+    /// The whole line containing the offending text
+    pub text: String,
+    /// Description of what was expected at `span`
+    pub expected: String
+}
+
+impl ParseError {
+    pub fn new(text: &str, span: Range<usize>, expected: impl Into<String>) -> ParseError {
+        ParseError {
+            day: None,
+            line: None,
+            span,
+            text: text.to_string(),
+            expected: expected.into()
+        }
+    }
+
+    /// Creates an error for the sub-slice `found` of `text`
+    ///
+    /// `found` must point into `text`, e.g. a token returned by `str::split`.
+    pub fn at(text: &str, found: &str, expected: impl Into<String>) -> ParseError {
+        let start = offset_of(text, found);
+        ParseError::new(text, start..start + found.len(), expected)
+    }
+
+    /// Creates an error for a missing token at the end of `text`
+    pub fn at_end(text: &str, expected: impl Into<String>) -> ParseError {
+        ParseError::new(text, text.len()..text.len(), expected)
+    }
+
+    pub fn in_day(mut self, day: u8) -> ParseError {
+        self.day = Some(day);
+        self
+    }
+
+    pub fn at_line(mut self, line: usize) -> ParseError {
+        self.line = Some(line);
+        self
+    }
+
+    /// Re-bases an error raised for `part` onto the line `text` that contains it
+    pub fn within(mut self, text: &str, part: &str) -> ParseError {
+        let offset = offset_of(text, part);
+        self.span = self.span.start + offset..self.span.end + offset;
+        self.text = text.to_string();
+        self
+    }
+
+    /// The offending text, which is empty when a token is missing
+    pub fn found(&self) -> &str {
+        self.text.get(self.span.clone()).unwrap_or("")
+    }
+}
+
+fn offset_of(text: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+    if offset <= text.len() {
+        offset
+    } else {
+        0
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}, ")?;
+        }
+
+        if let Some(line) = self.line {
+            write!(f, "line {line}, ")?;
+        }
+
+        write!(f, "column {}: expected {}", self.span.start + 1, self.expected)?;
+        match self.found() {
+            "" => write!(f, ", found end of line")?,
+            found => write!(f, ", found `{found}`")?
+        }
+
+        let line_number = self.line.map(|line| line.to_string()).unwrap_or_default();
+        let gutter = " ".repeat(line_number.len());
+        let padding = " ".repeat(self.text.get(..self.span.start).map_or(0, |s| s.chars().count()));
+        let carets = "^".repeat(self.found().chars().count().max(1));
+        write!(f, "\n{gutter} |\n{line_number} | {}\n{gutter} | {padding}{carets}", self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::ParseError;
+
+    #[test]
+    fn render_caret() {
+        let line = "D Y";
+        let err = ParseError::at(line, &line[..1], "one of `A`, `B` or `C`").in_day(2).at_line(3);
+
+        assert_eq!(err.to_string(), "\
+day 2, line 3, column 1: expected one of `A`, `B` or `C`, found `D`
+  |
+3 | D Y
+  | ^")
+    }
+
+    #[test]
+    fn render_missing_token() {
+        let err = ParseError::at_end("2-4", "`,`").at_line(12);
+
+        assert_eq!(err.to_string(), "\
+line 12, column 4: expected `,`, found end of line
+   |
+12 | 2-4
+   |    ^")
+    }
+
+    #[test]
+    fn within_line() {
+        let line = "2-4,6-x";
+        let part = &line[4..];
+        let err = ParseError::at(part, &part[2..], "end of section").within(line, part);
+
+        assert_eq!((err.span.clone(), err.found()), (6..7, "x"))
+    }
+}
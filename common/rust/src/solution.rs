@@ -0,0 +1,35 @@
+use std::fmt;
+use std::io::BufRead;
+use std::path::Path;
+use crate::{open, Result};
+
+/// A solved puzzle for one day, exposing the answers to both parts
+pub trait Solution: Sized {
+    /// Day of the advent calendar, starting at 1
+    const DAY: u8;
+
+    /// Puzzle title, e.g. "Calorie Counting"
+    const TITLE: &'static str;
+
+    type Part1: fmt::Display;
+    type Part2: fmt::Display;
+
+    /// Solves the puzzle from any buffered `reader`
+    fn from_reader<R>(reader: R) -> Result<Self>
+    where R: BufRead;
+
+    fn part_1(&self) -> Self::Part1;
+
+    fn part_2(&self) -> Self::Part2;
+
+    /// Solves the puzzle from a file path `filename`
+    fn from_file<P>(filename: P) -> Result<Self>
+    where P: AsRef<Path> {
+        Self::from_reader(open(filename)?)
+    }
+
+    /// Solves the puzzle from a string slice `input`
+    fn from_input(input: &str) -> Result<Self> {
+        Self::from_reader(input.as_bytes())
+    }
+}
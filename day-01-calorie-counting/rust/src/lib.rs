@@ -0,0 +1,146 @@
+use std::fmt;
+use std::io::BufRead;
+use std::path::Path;
+use aoc_common::{records, ParseError, Record, Result, Solution, TryLine};
+
+/// Solves Day 1 from a file path `filename`
+pub fn run<P>(filename: P) -> Result<TopCalories>
+where P: AsRef<Path> {
+    TopCalories::from_file(filename)
+}
+
+/// Solves Day 1 from a string slice `input`
+pub fn run_lines(input: &str) -> Result<TopCalories> {
+    TopCalories::from_lines(input.lines())
+}
+
+#[derive(Debug)]
+pub struct TopCalories {
+    top: Vec<i32>
+}
+
+impl TopCalories {
+    fn new(capacity: usize) -> Self {
+        TopCalories {
+            top: vec![0; capacity]
+        }
+    }
+
+    fn from_lines<I>(lines: I) -> Result<Self>
+    where I: IntoIterator, I::Item: TryLine, aoc_common::Error: From<<I::Item as TryLine>::Error> {
+        let mut top_calories = TopCalories::new(3);
+        for record in records(lines) {
+            top_calories.handle_elf(&record?)?;
+        }
+
+        Ok(top_calories)
+    }
+
+    fn handle_elf<S>(&mut self, record: &Record<S>) -> Result<()>
+    where S: AsRef<str> {
+        let mut elf_calories = 0;
+        for (line_number, line) in record.numbered() {
+            let line = line.as_ref();
+            elf_calories += line.parse::<i32>()
+                .map_err(|_| ParseError::at(line, line, "number of Calories").in_day(Self::DAY).at_line(line_number))?;
+        }
+
+        self.add_if_top(elf_calories);
+        Ok(())
+    }
+
+    fn add_if_top(&mut self, calories: i32) -> Option<i32> {
+        let mut ranking: Option<i32> = None;
+        let mut prev_top_i = 0;
+        for (i, top_i) in self.top.iter_mut().enumerate() {
+            let current = *top_i;
+            match ranking {
+                Some(_) => *top_i = prev_top_i,
+                None => {
+                    if calories > current {
+                        *top_i = calories;
+                        ranking = Some(1 + i as i32);
+                    }
+                }
+            }
+            prev_top_i = current;
+        }
+
+        ranking
+    }
+}
+
+impl Solution for TopCalories {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Calorie Counting";
+
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn from_reader<R>(reader: R) -> Result<Self>
+    where R: BufRead {
+        TopCalories::from_lines(reader.lines())
+    }
+
+    fn part_1(&self) -> i32 {
+        self.top[0]
+    }
+
+    fn part_2(&self) -> i32 {
+        self.top.iter().sum()
+    }
+}
+
+impl fmt::Display for TopCalories {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let n = self.top.len();
+        write!(f, "Top Calories: Top 1: {}, Total of Top {}: {}", self.part_1(), n, self.part_2())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_common::{Error, Solution};
+    use crate::run_lines;
+
+    const EXAMPLE: &str = "\
+1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000
+";
+
+    #[test]
+    fn part_1() {
+        let results = run_lines(EXAMPLE).unwrap();
+
+        assert_eq!(results.part_1(), 24_000)
+    }
+
+    #[test]
+    fn part_2() {
+        let results = run_lines(EXAMPLE).unwrap();
+
+        assert_eq!(results.part_2(), 45_000)
+    }
+
+    #[test]
+    fn parse_error_line_number() {
+        let err = match run_lines("1000\n\n2000\n3OOO\n") {
+            Err(Error::Parse(err)) => err,
+            other => panic!("expected parse error, got {other:?}")
+        };
+
+        assert_eq!((err.day, err.line, err.span), (Some(1), Some(4), 0..4))
+    }
+}
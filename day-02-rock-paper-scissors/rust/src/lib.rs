@@ -1,32 +1,19 @@
-use std::error::Error;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::path::Path;
+use aoc_common::{ParseError, Result, Solution, TryLine};
 
 /// Solves Day 2 from a file path `filename`
-pub fn run<P>(filename: P) -> Result<Results, Box<dyn Error>>
+pub fn run<P>(filename: P) -> Result<Results>
 where P: AsRef<Path> {
-    let mut results = Results::new();
-    let lines = read_lines(filename)?;
-    for l in lines {
-        let line = l?;
-        results.handle_line(&line)?;
-    }
-
-    Ok(results)
+    Results::from_file(filename)
 }
 
 /// Solves Day 2 from a string slice `input`
-pub fn run_lines(input: &str) -> Result<Results, Box<dyn Error>> {
-    let mut results = Results::new();
-    for line in input.lines() {
-        results.handle_line(&line)?;
-    }
-
-    Ok(results)
+pub fn run_lines(input: &str) -> Result<Results> {
+    Results::from_lines(input.lines())
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Results {
     score_1: i32,
     score_2: i32
@@ -37,8 +24,19 @@ pub fn new() -> Results {
         Results { score_1: 0, score_2: 0 }
     }
 
-    pub fn handle_line(&mut self, line: &str) -> Result<(), Box<dyn Error>> {
-        let round = Round::parse(&line).ok_or_else(|| format!("error parsing line {line}"))?;
+    fn from_lines<I>(lines: I) -> Result<Self>
+    where I: IntoIterator, I::Item: TryLine, aoc_common::Error: From<<I::Item as TryLine>::Error> {
+        let mut results = Results::new();
+        for (i, l) in lines.into_iter().enumerate() {
+            let line = l.try_line()?;
+            results.handle_line(line.as_ref()).map_err(|err| err.at_line(i + 1))?;
+        }
+
+        Ok(results)
+    }
+
+    pub fn handle_line(&mut self, line: &str) -> std::result::Result<(), ParseError> {
+        let round = Round::parse(line).map_err(|err| err.in_day(Self::DAY))?;
 
         self.score_1 += round.score_1();
         self.score_2 += round.score_2();
@@ -46,39 +44,57 @@ pub fn handle_line(&mut self, line: &str) -> Result<(), Box<dyn Error>> {
     }
 }
 
+impl Solution for Results {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Rock Paper Scissors";
+
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn from_reader<R>(reader: R) -> Result<Self>
+    where R: BufRead {
+        Results::from_lines(reader.lines())
+    }
+
+    fn part_1(&self) -> i32 {
+        self.score_1
+    }
+
+    fn part_2(&self) -> i32 {
+        self.score_2
+    }
+}
+
 pub struct Round {
     opponent: Shape,
     unknown: Unknown
 }
 
 impl Round {
-    pub fn parse(s: &str) -> Option<Round> {
-        let tokens = s.split_ascii_whitespace();
-        let mut opponent: Option<Shape> = None;
-        let mut unknown: Option<Unknown> = None;
-        let mut i = 0;
-        for token in tokens {
-            match i {
-                0 => opponent = match token {
-                    "A" => Some(Shape::Rock),
-                    "B" => Some(Shape::Paper),
-                    "C" => Some(Shape::Scissors),
-                    _ => return None
-                },
-                1 => unknown = match token {
-                    "X" => Some(Unknown::X),
-                    "Y" => Some(Unknown::Y),
-                    "Z" => Some(Unknown::Z),
-                    _ => return None
-                },
-                _ => return None
-            }
-            i += 1;
-        }
+    pub fn parse(s: &str) -> std::result::Result<Round, ParseError> {
+        const EXPECTED_OPPONENT: &str = "one of `A`, `B` or `C`";
+        const EXPECTED_UNKNOWN: &str = "one of `X`, `Y` or `Z`";
+
+        let mut tokens = s.split_ascii_whitespace();
+        let opponent = match tokens.next() {
+            Some("A") => Shape::Rock,
+            Some("B") => Shape::Paper,
+            Some("C") => Shape::Scissors,
+            Some(token) => return Err(ParseError::at(s, token, EXPECTED_OPPONENT)),
+            None => return Err(ParseError::at_end(s, EXPECTED_OPPONENT))
+        };
+
+        let unknown = match tokens.next() {
+            Some("X") => Unknown::X,
+            Some("Y") => Unknown::Y,
+            Some("Z") => Unknown::Z,
+            Some(token) => return Err(ParseError::at(s, token, EXPECTED_UNKNOWN)),
+            None => return Err(ParseError::at_end(s, EXPECTED_UNKNOWN))
+        };
 
-        match i {
-            2 => Some(Round { opponent: opponent?, unknown: unknown? }),
-            _ => None
+        match tokens.next() {
+            Some(token) => Err(ParseError::at(s, token, "end of line")),
+            None => Ok(Round { opponent, unknown })
         }
     }
 
@@ -169,9 +185,42 @@ fn response(&self, opponent: &Shape) -> Shape {
     }
 }
 
-// from https://doc.rust-lang.org/rust-by-example/std_misc/file/read_lines.html
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
+#[cfg(test)]
+mod tests {
+    use aoc_common::Error;
+    use crate::run_lines;
+
+    const EXAMPLE: &str = "\
+A Y
+B X
+C Z
+";
+
+    #[test]
+    fn part_1() {
+        let results = run_lines(EXAMPLE).unwrap();
+
+        assert_eq!(results.score_1, 15)
+    }
+
+    #[test]
+    fn part_2() {
+        let results = run_lines(EXAMPLE).unwrap();
+
+        assert_eq!(results.score_2, 12)
+    }
+
+    #[test]
+    fn parse_error() {
+        let err = match run_lines("A Y\nB W\n") {
+            Err(Error::Parse(err)) => err,
+            other => panic!("expected parse error, got {other:?}")
+        };
+
+        assert_eq!(err.to_string(), "\
+day 2, line 2, column 3: expected one of `X`, `Y` or `Z`, found `W`
+  |
+2 | B W
+  |   ^")
+    }
 }
@@ -1,44 +1,16 @@
-use std::error::Error;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::path::Path;
+use aoc_common::{chunks, Error, Result, Solution, TryLine};
 
 /// Solves Day 3 from a file path `filename`
-pub fn run<P>(filename: P) -> Result<Results, Box<dyn Error>>
+pub fn run<P>(filename: P) -> Result<Results>
 where P: AsRef<Path> {
-    let mut results = Results::new();
-    let mut i = 0;
-    let mut group_lines = [String::from(""), String::from(""), String::from("")];
-    let lines = read_lines(filename)?;
-    for line in lines {
-        let num = i % 3;
-        group_lines[num] = line?;
-        if num == 2 {
-            let group = Group::new(group_lines[0].as_str(), group_lines[1].as_str(), group_lines[2].as_str());
-            results.handle_group(&group)?;
-        }
-        i += 1;
-    }
-
-    Ok(results)
+    Results::from_file(filename)
 }
 
 /// Solves Day 3 from a string slice `input`
-pub fn run_lines(input: &str) -> Result<Results, Box<dyn Error>> {
-    let mut results = Results::new();
-    let mut i = 0;
-    let mut group_lines = [""; 3];
-    for line in input.lines() {
-        let num = i % 3;
-        group_lines[num] = line;
-        if num == 2 {
-            let group = Group::new(group_lines[0], group_lines[1], group_lines[2]);
-            results.handle_group(&group)?;
-        }
-        i += 1;
-    }
-
-    Ok(results)
+pub fn run_lines(input: &str) -> Result<Results> {
+    Results::from_lines(input.lines())
 }
 
 #[derive(Debug)]
@@ -52,17 +24,50 @@ fn new() -> Results {
         Results { sum_1: 0, sum_2: 0 }
     }
 
-    fn handle_group(&mut self, group: &Group) -> Result<(), Box<dyn Error>> {
+    fn from_lines<I>(lines: I) -> Result<Self>
+    where I: IntoIterator, I::Item: TryLine, aoc_common::Error: From<<I::Item as TryLine>::Error> {
+        let mut results = Results::new();
+        for chunk in chunks::<3, _>(lines) {
+            let [sack_1, sack_2, sack_3] = chunk?;
+            let group = Group::new(sack_1.as_ref(), sack_2.as_ref(), sack_3.as_ref());
+            results.handle_group(&group)?;
+        }
+
+        Ok(results)
+    }
+
+    fn handle_group(&mut self, group: &Group) -> Result<()> {
         self.sum_1 += group.compartment_priorities_sum()
-            .ok_or_else(|| "common compartment item not found for one or more rucksacks in group")?;
+            .ok_or_else(|| Error::solve("common compartment item not found for one or more rucksacks in group"))?;
 
         self.sum_2 += group.badge_priority()
-            .ok_or_else(|| "badge not found for group")?;
+            .ok_or_else(|| Error::solve("badge not found for group"))?;
 
         Ok(())
     }
 }
 
+impl Solution for Results {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Rucksack Reorganization";
+
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn from_reader<R>(reader: R) -> Result<Self>
+    where R: BufRead {
+        Results::from_lines(reader.lines())
+    }
+
+    fn part_1(&self) -> i32 {
+        self.sum_1
+    }
+
+    fn part_2(&self) -> i32 {
+        self.sum_2
+    }
+}
+
 // it's more complex to use lifetimes `&'a str` vs. String, but it allows us to avoid string copies from run_lines
 struct Group<'a> {
     sack_1: &'a str,
@@ -98,7 +103,7 @@ fn both_compartments_priority(&self, num: usize) -> Option<i32> {
         let (first, last) = sack.split_at(sack.len() / 2);
         let common_item = find_common_compartment_item(first, last)?;
 
-        Some(priority(common_item)?)
+        priority(common_item)
     }
 
     fn badge_priority(&self) -> Option<i32> {
@@ -138,18 +143,12 @@ fn priority(c: char) -> Option<i32> {
     }
 }
 
-// from https://doc.rust-lang.org/rust-by-example/std_misc/file/read_lines.html
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
-    use crate::run_lines;
+    use aoc_common::Solution;
+    use crate::{run_lines, Results};
 
-    const EXAMPLE: &'static str = "\
+    const EXAMPLE: &str = "\
 vJrwpWtwJgWrhcsFMMfFFhFp
 jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
 PmmdzqPrVvPwwTWBwg
@@ -170,4 +169,11 @@ fn part_2() {
 
         assert_eq!(results.sum_2, 70)
     }
+
+    #[test]
+    fn from_reader() {
+        let results = Results::from_reader(EXAMPLE.as_bytes()).unwrap();
+
+        assert_eq!((results.part_1(), results.part_2()), (157, 70))
+    }
 }
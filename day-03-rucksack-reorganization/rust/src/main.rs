@@ -1,8 +1,8 @@
-use std::error::Error;
 use std::path::Path;
+use aoc_common::Result;
 use day_03_rucksack_reorganization::run;
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn main() -> Result<()> {
     let filename = Path::new("../input.txt");
 
     let scores = run(filename)?;
@@ -1,33 +1,17 @@
-use std::error::Error;
-use std::fmt;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::path::Path;
 use std::str::FromStr;
+use aoc_common::{ParseError, Solution, TryLine};
 
 /// Solves Day 4 from a file path `filename`
-pub fn run<P>(filename: P) -> Result<Results, Box<dyn Error>>
+pub fn run<P>(filename: P) -> aoc_common::Result<Results>
 where P: AsRef<Path> {
-    let mut results = Results::new();
-    let lines = read_lines(filename)?;
-    for l in lines {
-        let line = l?;
-        let pair = line.parse::<AssignmentPair>()?;
-        results.handle_pair(&pair);
-    }
-
-    Ok(results)
+    Results::from_file(filename)
 }
 
 /// Solves Day 4 from a string slice `input`
-pub fn run_lines(input: &str) -> Result<Results, Box<dyn Error>> {
-    let mut results = Results::new();
-    for line in input.lines() {
-        let pair = line.parse::<AssignmentPair>()?;
-        results.handle_pair(&pair);
-    }
-
-    Ok(results)
+pub fn run_lines(input: &str) -> aoc_common::Result<Results> {
+    Results::from_lines(input.lines())
 }
 
 #[derive(Debug)]
@@ -41,6 +25,18 @@ fn new() -> Results {
         Results { count_1: 0, count_2: 0 }
     }
 
+    fn from_lines<I>(lines: I) -> aoc_common::Result<Self>
+    where I: IntoIterator, I::Item: TryLine, aoc_common::Error: From<<I::Item as TryLine>::Error> {
+        let mut results = Results::new();
+        for (i, l) in lines.into_iter().enumerate() {
+            let line = l.try_line()?;
+            let pair = line.as_ref().parse::<AssignmentPair>().map_err(|err| err.at_line(i + 1))?;
+            results.handle_pair(&pair);
+        }
+
+        Ok(results)
+    }
+
     fn handle_pair(&mut self, pair: &AssignmentPair) {
         let fully_contains = pair.elf_1.fully_contains(&pair.elf_2) || pair.elf_2.fully_contains(&pair.elf_1);
         if fully_contains {
@@ -54,48 +50,46 @@ fn handle_pair(&mut self, pair: &AssignmentPair) {
     }
 }
 
+impl Solution for Results {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Camp Cleanup";
+
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn from_reader<R>(reader: R) -> aoc_common::Result<Self>
+    where R: BufRead {
+        Results::from_lines(reader.lines())
+    }
+
+    fn part_1(&self) -> i32 {
+        self.count_1
+    }
+
+    fn part_2(&self) -> i32 {
+        self.count_2
+    }
+}
+
 struct AssignmentPair {
     elf_1: Assignment,
     elf_2: Assignment
 }
 
 impl FromStr for AssignmentPair {
-    type Err = AssignmentPairError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (first, second) = s.split_once(',')
-            .ok_or_else(|| AssignmentPairError::new("failed to find delimiter ','"))?;
+            .ok_or_else(|| ParseError::at_end(s, "delimiter `,`").in_day(Results::DAY))?;
 
         Ok(AssignmentPair {
-            elf_1: first.parse::<Assignment>()?,
-            elf_2: second.parse::<Assignment>()?
+            elf_1: first.parse::<Assignment>().map_err(|err| err.within(s, first))?,
+            elf_2: second.parse::<Assignment>().map_err(|err| err.within(s, second))?
         })
     }
 }
 
-#[derive(Debug)]
-struct AssignmentPairError {
-    details: String
-}
-
-impl AssignmentPairError {
-    fn new(details: &str) -> AssignmentPairError {
-        AssignmentPairError { details: String::from(details) }
-    }
-}
-
-impl fmt::Display for AssignmentPairError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.details)
-    }
-}
-
-impl Error for AssignmentPairError {
-    fn description(&self) -> &str {
-        &self.details
-    }
-}
-
 struct Assignment {
     start: i32,
     end: i32
@@ -113,30 +107,26 @@ fn overlaps(&self, other: &Assignment) -> bool {
 }
 
 impl FromStr for Assignment {
-    type Err = AssignmentPairError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (start_text, end_text) = s.split_once('-')
-            .ok_or_else(|| AssignmentPairError::new("failed to find delimiter '-'"))?;
-        let start = start_text.parse::<i32>().map_err(|_| AssignmentPairError::new("failed to parse start"))?;
-        let end = end_text.parse::<i32>().map_err(|_| AssignmentPairError::new("failed to parse end"))?;
-    
+            .ok_or_else(|| ParseError::at_end(s, "delimiter `-`").in_day(Results::DAY))?;
+        let start = start_text.parse::<i32>()
+            .map_err(|_| ParseError::at(s, start_text, "start section ID").in_day(Results::DAY))?;
+        let end = end_text.parse::<i32>()
+            .map_err(|_| ParseError::at(s, end_text, "end section ID").in_day(Results::DAY))?;
+
         Ok(Assignment { start, end })
     }
 }
 
-// from https://doc.rust-lang.org/rust-by-example/std_misc/file/read_lines.html
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
+    use aoc_common::Error;
     use crate::run_lines;
 
-    const EXAMPLE: &'static str = "\
+    const EXAMPLE: &str = "\
 2-4,6-8
 2-3,4-5
 5-7,7-9
@@ -158,4 +148,24 @@ fn part_2() {
 
         assert_eq!(results.count_2, 4)
     }
+
+    #[test]
+    fn parse_error_span() {
+        let err = match run_lines("2-4,6-8\n2-3,4-x\n") {
+            Err(Error::Parse(err)) => err,
+            other => panic!("expected parse error, got {other:?}")
+        };
+
+        assert_eq!((err.day, err.line, err.span.clone(), err.found()), (Some(4), Some(2), 6..7, "x"))
+    }
+
+    #[test]
+    fn parse_error_missing_delimiter() {
+        let err = match run_lines("2-4 6-8") {
+            Err(Error::Parse(err)) => err,
+            other => panic!("expected parse error, got {other:?}")
+        };
+
+        assert_eq!((err.span, err.expected.as_str()), (7..7, "delimiter `,`"))
+    }
 }
@@ -1,4 +1,5 @@
 use std::fs;
+use aoc_common::Solution;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use day_06_tuning_trouble::{run, run_lines};
 
@@ -21,5 +22,76 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         }));
 }
 
-criterion_group!(benches, criterion_benchmark);
+/// Compares the single pass marker search with the window by window search it replaced,
+/// on the puzzle input and on a stream whose markers only come at the end
+pub fn marker_benchmark(c: &mut Criterion) {
+    let contents = fs::read_to_string("../input.txt").expect("failed to read file");
+    let streams = [("input.txt", contents), ("synthetic 4 MiB", synthetic_stream(4 << 20))];
+
+    let mut group = c.benchmark_group("day 6 markers");
+    for (name, stream) in &streams {
+        let line = stream.lines().next().expect("missing stream");
+        assert_eq!(window_by_window::markers(line), run_lines(line).map(|results| (results.part_1(), results.part_2())).ok());
+
+        group.bench_with_input(BenchmarkId::new("window by window", name), line, |b, line| b.iter(|| {
+            window_by_window::markers(line)
+        }));
+        group.bench_with_input(BenchmarkId::new("single pass", name), line, |b, line| b.iter(|| {
+            run_lines(line)
+        }));
+    }
+    group.finish();
+}
+
+/// A stream of `len` bytes drawn from 3 letters, then from 13, so neither marker turns up until its second half
+/// and the message marker only at the very end
+fn synthetic_stream(len: usize) -> String {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut letter = |alphabet: u64| {
+        // xorshift, so the stream is the same on every run
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        char::from(b'a' + (state % alphabet) as u8)
+    };
+
+    let mut stream = String::with_capacity(len);
+    stream.extend((0..len / 2).map(|_| letter(3)));
+    stream.extend((len / 2..len - 14).map(|_| letter(13)));
+    stream.push_str("abcdefghijklmn");
+    stream
+}
+
+/// The marker search from before the single pass, which checked every window pair by pair
+mod window_by_window {
+    pub fn markers(s: &str) -> Option<(i32, i32)> {
+        Some((find_first_marker(s, 4)?, find_first_marker(s, 14)?))
+    }
+
+    fn find_first_marker(s: &str, n: usize) -> Option<i32> {
+        for i in n-1..s.len() {
+            let potential_marker = &s[i+1-n..=i];
+            if is_marker(potential_marker) {
+                return Some(1 + i as i32)
+            }
+        }
+
+        None
+    }
+
+    fn is_marker(s: &str) -> bool {
+        let bytes = s.as_bytes();
+        for i in 0..bytes.len() {
+            for j in i+1..bytes.len() {
+                if bytes[i] == bytes[j] {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+criterion_group!(benches, criterion_benchmark, marker_benchmark);
 criterion_main!(benches);
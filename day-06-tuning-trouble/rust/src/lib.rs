@@ -1,29 +1,22 @@
 use std::error::Error;
 use std::fmt;
-use std::fs::File;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::path::Path;
 use std::str::FromStr;
+use aoc_common::Solution;
 
 /// Solves Day 6 from a file path `filename`
-pub fn run<P>(filename: P) -> Result<Results, Box<dyn Error>>
+pub fn run<P>(filename: P) -> aoc_common::Result<Results>
 where P: AsRef<Path> {
-    let lines = read_lines(filename)?;
-    for l in lines {
-        let line = &l?;
-        return Ok(line.parse::<Results>()?);
-    }
-
-    Err(Box::new(MessageProcessingError::new("message not found")))
+    Results::from_file(filename)
 }
 
 /// Solves Day 6 from a string slice `input`
-pub fn run_lines(input: &str) -> Result<Results, Box<dyn Error>> {
-    for line in input.lines() {
-        return Ok(line.parse::<Results>()?);
-    }
+pub fn run_lines(input: &str) -> aoc_common::Result<Results> {
+    let line = input.lines().next()
+        .ok_or_else(|| MessageProcessingError::new("message not found"))?;
 
-    Err(Box::new(MessageProcessingError::new("message not found")))
+    Ok(line.parse::<Results>()?)
 }
 
 #[derive(Debug)]
@@ -46,6 +39,30 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
     }
 }
 
+impl Solution for Results {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Tuning Trouble";
+
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn from_reader<R>(reader: R) -> aoc_common::Result<Self>
+    where R: BufRead {
+        let line = reader.lines().next()
+            .ok_or_else(|| MessageProcessingError::new("message not found"))??;
+
+        Ok(line.parse::<Results>()?)
+    }
+
+    fn part_1(&self) -> i32 {
+        self.packet_marker
+    }
+
+    fn part_2(&self) -> i32 {
+        self.message_marker
+    }
+}
+
 enum MarkerType {
     Packet,
     Message
@@ -74,15 +91,28 @@ fn description(&self) -> &str {
     }
 }
 
+impl From<MessageProcessingError> for aoc_common::Error {
+    fn from(err: MessageProcessingError) -> Self {
+        aoc_common::Error::Solve(err.details)
+    }
+}
+
+/// Finds the position just past the first run of distinct characters as long as the marker,
+/// in one pass that tracks where each byte was last seen
 fn find_first_marker(s: &str, marker_type: MarkerType) -> Option<i32> {
     let n = match marker_type {
         MarkerType::Packet => 4,
         MarkerType::Message => 14
     };
 
-    for i in n-1..s.len() {
-        let potential_marker = &s[i+1-n..=i];
-        if is_marker(potential_marker) {
+    // one past the index each byte was last seen at, so 0 means never
+    let mut last_seen = [0; 256];
+    // start of the longest run of distinct bytes ending at the current one
+    let mut start = 0;
+    for (i, &b) in s.as_bytes().iter().enumerate() {
+        start = start.max(last_seen[b as usize]);
+        last_seen[b as usize] = i + 1;
+        if i + 1 - start == n {
             return Some(1 + i as i32)
         }
     }
@@ -90,35 +120,15 @@ fn find_first_marker(s: &str, marker_type: MarkerType) -> Option<i32> {
     None
 }
 
-fn is_marker(s: &str) -> bool {
-    let bytes = s.as_bytes();
-    for i in 0..bytes.len() {
-        for j in i+1..bytes.len() {
-            if &bytes[i] == &bytes[j] {
-                return false;
-            }
-        }
-    }
-
-    true
-}
-
-// from https://doc.rust-lang.org/rust-by-example/std_misc/file/read_lines.html
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<File>>>
-where P: AsRef<Path>, {
-    let file = File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
-}
-
 #[cfg(test)]
 mod tests {
     use crate::run_lines;
 
-    const EXAMPLE_A: &'static str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
-    const EXAMPLE_B: &'static str = "bvwbjplbgvbhsrlpgdmjqwftvncz";
-    const EXAMPLE_C: &'static str = "nppdvjthqldpwncqszvftbrmjlhg";
-    const EXAMPLE_D: &'static str = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
-    const EXAMPLE_E: &'static str = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
+    const EXAMPLE_A: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+    const EXAMPLE_B: &str = "bvwbjplbgvbhsrlpgdmjqwftvncz";
+    const EXAMPLE_C: &str = "nppdvjthqldpwncqszvftbrmjlhg";
+    const EXAMPLE_D: &str = "nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg";
+    const EXAMPLE_E: &str = "zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw";
 
     #[test]
     fn part_1_a() {
@@ -189,4 +199,18 @@ fn part_2_e() {
 
         assert_eq!(results.message_marker, 26)
     }
+
+    #[test]
+    fn marker_after_repeats() {
+        let results = run_lines("abcabcabcdabcdefghijklmabcdefghijklmn").unwrap();
+
+        assert_eq!((results.packet_marker, results.message_marker), (10, 37))
+    }
+
+    #[test]
+    fn message_marker_not_found() {
+        let err = run_lines("abcdefghijklmabcdefghijklm").unwrap_err();
+
+        assert_eq!(err.to_string(), "failed to solve: failed to find first message marker")
+    }
 }
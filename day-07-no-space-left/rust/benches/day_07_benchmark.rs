@@ -0,0 +1,188 @@
+use std::fs;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use day_07_no_space_left::{generate, run, run_lines, GeneratorOptions, NodeRef, Tree, TreeOptions};
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    const FILENAME: &str = "../input.txt";
+    let contents = fs::read_to_string(FILENAME).expect("failed to read file");
+
+    c.bench_with_input(
+        BenchmarkId::new("day 7", "input.txt"),
+        &FILENAME,
+        |b, f| b.iter(|| {
+            run(f)
+        }));
+
+    c.bench_with_input(
+        BenchmarkId::new("day 7", "in memory"),
+        &contents,
+        |b, c| b.iter(|| {
+            run_lines(c)
+        }));
+}
+
+/// Builds, navigates and sizes a generated filesystem of over 100k files and directories
+pub fn synthetic_benchmark(c: &mut Criterion) {
+    let options = GeneratorOptions { directories: 20_000, files: 100_000, max_file_size: 1_000_000, revisits: 1_000 };
+    let generated = generate(1, &options);
+    let mut tree = Tree::from_input(&generated.transcript, &TreeOptions::default()).expect("invalid transcript");
+
+    // every directory by absolute path, then by `cd ..` and a relative path from its parent
+    let cd_lines = generated.directory_sizes.keys()
+        .flat_map(|path| match path.rsplit_once('/') {
+            Some((_, name)) if !name.is_empty() => vec![format!("$ cd {path}"), String::from("$ cd .."), format!("$ cd {name}")],
+            _ => vec![String::from("$ cd /")]
+        })
+        .collect::<Vec<_>>();
+
+    c.bench_with_input(
+        BenchmarkId::new("day 7 synthetic", "rebuild"),
+        &generated.transcript,
+        |b, t| b.iter(|| {
+            Tree::from_input(t, &TreeOptions::default())
+        }));
+
+    c.bench_function(
+        "day 7 synthetic/cd",
+        |b| b.iter(|| {
+            for line in &cd_lines {
+                tree.push_line(line).expect("invalid cd");
+            }
+        }));
+
+    c.bench_function(
+        "day 7 synthetic/sizes",
+        |b| b.iter(|| {
+            tree.recalculate_sizes()
+        }));
+}
+
+/// Lists and enters directories of `width` entries, half files and half subdirectories,
+/// then lists `/` a second time
+pub fn wide_benchmark(c: &mut Criterion) {
+    let width = 10_000;
+    let mut transcript = String::from("$ cd /\n$ ls\n");
+    let listing = (0..width / 2).map(|i| format!("dir d{i}\n{i} f{i}\n")).collect::<String>();
+    transcript.push_str(&listing);
+    for i in 0..width / 2 {
+        transcript.push_str(&format!("$ cd d{i}\n$ cd ..\n"));
+    }
+    transcript.push_str("$ ls\n");
+    transcript.push_str(&listing);
+
+    c.bench_with_input(
+        BenchmarkId::new("day 7 wide", width),
+        &transcript,
+        |b, t| b.iter(|| {
+            Tree::from_input(t, &TreeOptions::default())
+        }));
+}
+
+/// A chain of `depth` directories, each holding a one byte file
+fn deep_transcript(depth: usize) -> String {
+    "$ ls\ndir d\n1 f\n$ cd d\n".repeat(depth)
+}
+
+/// `/` holding `dirs` directories of `files` files each
+fn wide_transcript(dirs: usize, files: usize) -> String {
+    let mut transcript = String::from("$ cd /\n$ ls\n");
+    for d in 0..dirs {
+        transcript.push_str(&format!("dir d{d}\n"));
+    }
+
+    for d in 0..dirs {
+        transcript.push_str(&format!("$ cd d{d}\n$ ls\n"));
+        for f in 0..files {
+            transcript.push_str(&format!("{} f{f}\n", f + 1));
+        }
+        transcript.push_str("$ cd ..\n");
+    }
+
+    transcript
+}
+
+/// A copy of a tree's nodes, laid out like `Tree`'s own, for the recursive size calculation
+/// it used before directories were summed with a stack
+#[allow(dead_code)]
+enum Node {
+    Directory { name: String, parent_index: Option<usize>, children_indices: Vec<usize>, size: Option<u64> },
+    File { name: String, parent_index: usize, size: u64 }
+}
+
+fn copy_nodes(tree: &Tree) -> Vec<Node> {
+    let mut nodes = vec![directory("/", None)];
+    let mut pending = vec![(0, tree.root())];
+    while let Some((index, dir)) = pending.pop() {
+        for child in dir.children() {
+            let child_index = nodes.len();
+            match child {
+                NodeRef::Directory(child_dir) => {
+                    nodes.push(directory(child_dir.name(), Some(index)));
+                    pending.push((child_index, child_dir));
+                },
+                NodeRef::File(file) => nodes.push(Node::File { name: file.name().to_string(), parent_index: index, size: file.size() })
+            }
+
+            if let Node::Directory { children_indices, .. } = &mut nodes[index] {
+                children_indices.push(child_index);
+            }
+        }
+    }
+
+    nodes
+}
+
+fn directory(name: &str, parent_index: Option<usize>) -> Node {
+    Node::Directory { name: name.to_string(), parent_index, children_indices: Vec::new(), size: None }
+}
+
+fn recursive_sizes(nodes: &[Node]) -> Option<u64> {
+    let mut sizes = vec![None; nodes.len()];
+    recursive_size(nodes, 0, &mut sizes)
+}
+
+fn recursive_size(nodes: &[Node], index: usize, sizes: &mut [Option<u64>]) -> Option<u64> {
+    if let Some(size) = sizes[index] {
+        return Some(size);
+    }
+
+    let mut size: u64 = 0;
+    if let Node::Directory { children_indices, .. } = &nodes[index] {
+        for child_index in children_indices {
+            let child_size = match &nodes[*child_index] {
+                Node::Directory { .. } => recursive_size(nodes, *child_index, sizes)?,
+                Node::File { size, .. } => *size
+            };
+            size = size.checked_add(child_size)?;
+        }
+    }
+
+    sizes[index] = Some(size);
+    Some(size)
+}
+
+pub fn size_benchmark(c: &mut Criterion) {
+    // deep enough to show the cost of recursing, without overflowing the stack
+    let trees = [("deep", deep_transcript(10_000)), ("wide", wide_transcript(1_000, 100))]
+        .map(|(name, transcript)| (name, Tree::from_input(&transcript, &TreeOptions::default()).expect("invalid transcript")));
+
+    for (name, mut tree) in trees {
+        let nodes = copy_nodes(&tree);
+
+        c.bench_with_input(
+            BenchmarkId::new("day 7 recursive sizes", name),
+            &nodes,
+            |b, n| b.iter(|| {
+                recursive_sizes(n)
+            }));
+
+        c.bench_function(
+            &format!("day 7 post-order sizes/{name}"),
+            |b| b.iter(|| {
+                tree.recalculate_sizes()
+            }));
+    }
+}
+
+criterion_group!(benches, criterion_benchmark, synthetic_benchmark, wide_benchmark, size_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,195 @@
+use std::path::{Path, PathBuf};
+use day_07_no_space_left::{ConflictPolicy, Glob, Parameters, Query, RenderOptions, ScanOptions, SymlinkPolicy, Traversal, TreeOptions, UnknownDirectoryPolicy};
+
+pub const USAGE: &str = "\
+Usage: day_07_no_space_left [OPTIONS]
+       day_07_no_space_left find [FIND OPTIONS]
+
+Options:
+    -i, --input <PATH>          terminal transcript to solve, or - to read stdin (default: ../input.txt)
+    -c, --capacity <BYTES>      total size of the disk (default: 70000000)
+    -u, --min-unused <BYTES>    unused space needed for the update (default: 30000000)
+    -s, --small-limit <BYTES>   largest directory counted in part 1 (default: 100000)
+        --unknown-dirs <POLICY> cd into a directory never listed as one of ignore, strict or create (default: ignore)
+        --conflicts <POLICY>    treat listings contradicting earlier ones as one of warn or error (default: warn)
+    -r, --render <VIEW>         print the filesystem instead of the answers, as one of:
+                                  tree     indented listing in the puzzle's notation
+                                  du       size of every file and directory, like du -a
+                                  largest  the largest directories
+    -L, --max-depth <DEPTH>     deepest level rendered or scanned, where / is at depth 0
+    -n, --count <COUNT>         number of directories rendered by largest (default: 10)
+    -H, --human-readable        render sizes in powers of 1024, e.g. 8.1M
+    -d, --diff <PATH>           print what changed between the input and the later transcript PATH
+    -t, --timeline              print the sizes after each command as CSV instead of the answers
+    -w, --watch <PATH>          add a CSV column with the size of PATH, e.g. /a/e (repeatable)
+        --scan <DIR>            print a transcript listing the directory DIR instead of the answers
+        --order <ORDER>         order DIR is scanned in, depth or breadth (default: depth)
+        --symlinks <POLICY>     scan symbolic links as one of skip, follow or file (default: skip)
+    -h, --help                  print this help
+
+Find options, printing the size and path of every matching file:
+    -i, --input <PATH>          terminal transcript to search, or - to read stdin (default: ../input.txt)
+    -n, --name <GLOB>           match names, or paths if GLOB holds a /, e.g. '*.log' or '/a/**/h.*'
+        --unknown-dirs <POLICY> cd into a directory never listed as one of ignore, strict or create (default: ignore)
+        --conflicts <POLICY>    treat listings contradicting earlier ones as one of warn or error (default: warn)
+    -e, --ext <EXT>             match files with the extension EXT, e.g. log
+        --min-size <BYTES>      match files of at least BYTES
+        --max-size <BYTES>      match files of at most BYTES
+    -x, --by-extension          print the number and total size of the matching files per extension
+    -H, --human-readable        print sizes in powers of 1024, e.g. 8.1M
+";
+
+pub enum Command {
+    Solve(Options),
+    Find(FindOptions),
+    Help
+}
+
+pub enum View {
+    Tree,
+    DiskUsage,
+    Largest
+}
+
+pub struct Options {
+    pub input: PathBuf,
+    pub tree_options: TreeOptions,
+    pub parameters: Parameters,
+    pub view: Option<View>,
+    pub render: RenderOptions,
+    pub count: usize,
+    pub diff: Option<PathBuf>,
+    pub timeline: bool,
+    pub watched: Vec<String>,
+    pub scan: Option<PathBuf>,
+    pub scan_options: ScanOptions
+}
+
+pub struct FindOptions {
+    pub input: PathBuf,
+    pub tree_options: TreeOptions,
+    pub query: Query,
+    pub by_extension: bool,
+    pub human_readable: bool
+}
+
+/// Parses the command line `args`, excluding the program name
+pub fn parse<I>(args: I) -> Result<Command, String>
+where I: IntoIterator<Item = String> {
+    let mut args = args.into_iter().peekable();
+    if args.next_if(|arg| arg == "find").is_some() {
+        return parse_find(args);
+    }
+
+    let mut input = PathBuf::from("../input.txt");
+    let mut tree_options = TreeOptions::default();
+    let mut parameters = Parameters::default();
+    let mut view = None;
+    let mut render = RenderOptions::default();
+    let mut count = 10;
+    let mut diff = None;
+    let mut timeline = false;
+    let mut watched = Vec::new();
+    let mut scan = None;
+    let mut scan_options = ScanOptions::default();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {arg}"));
+        match arg.as_str() {
+            "-i" | "--input" => input = PathBuf::from(value()?),
+            "-c" | "--capacity" => parameters.capacity = parse_bytes(&value()?, "capacity")?,
+            "-u" | "--min-unused" => parameters.min_unused_space = parse_bytes(&value()?, "unused space")?,
+            "-s" | "--small-limit" => parameters.small_directory_limit = parse_bytes(&value()?, "small directory limit")?,
+            "--unknown-dirs" => tree_options.unknown_directory = parse_unknown_directory(&value()?)?,
+            "--conflicts" => tree_options.conflict = parse_conflict(&value()?)?,
+            "-r" | "--render" => view = Some(match value()?.as_str() {
+                "tree" => View::Tree,
+                "du" => View::DiskUsage,
+                "largest" => View::Largest,
+                other => return Err(format!("invalid view: {other}"))
+            }),
+            "-L" | "--max-depth" => render.max_depth = Some(parse_count(&value()?, "depth")?),
+            "-d" | "--diff" => diff = Some(PathBuf::from(value()?)),
+            "-t" | "--timeline" => timeline = true,
+            "-w" | "--watch" => watched.push(value()?),
+            "--scan" => scan = Some(PathBuf::from(value()?)),
+            "--order" => scan_options.traversal = match value()?.as_str() {
+                "depth" => Traversal::DepthFirst,
+                "breadth" => Traversal::BreadthFirst,
+                other => return Err(format!("invalid order: {other}"))
+            },
+            "--symlinks" => scan_options.symlinks = match value()?.as_str() {
+                "skip" => SymlinkPolicy::Skip,
+                "follow" => SymlinkPolicy::Follow,
+                "file" => SymlinkPolicy::AsFile,
+                other => return Err(format!("invalid symlink policy: {other}"))
+            },
+            "-n" | "--count" => count = parse_count(&value()?, "count")?,
+            "-H" | "--human-readable" => render.human_readable = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unknown option: {arg}"))
+        }
+    }
+
+    // stdin can only be read once
+    if input == Path::new("-") && diff.as_deref() == Some(Path::new("-")) {
+        return Err(String::from("the input and the later transcript of --diff can't both be read from stdin"));
+    }
+
+    scan_options.max_depth = render.max_depth;
+
+    Ok(Command::Solve(Options { input, tree_options, parameters, view, render, count, diff, timeline, watched, scan, scan_options }))
+}
+
+fn parse_find<I>(mut args: I) -> Result<Command, String>
+where I: Iterator<Item = String> {
+    let mut input = PathBuf::from("../input.txt");
+    let mut tree_options = TreeOptions::default();
+    let mut query = Query::default();
+    let mut by_extension = false;
+    let mut human_readable = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {arg}"));
+        match arg.as_str() {
+            "-i" | "--input" => input = PathBuf::from(value()?),
+            "--unknown-dirs" => tree_options.unknown_directory = parse_unknown_directory(&value()?)?,
+            "--conflicts" => tree_options.conflict = parse_conflict(&value()?)?,
+            "-n" | "--name" => query.glob = Some(Glob::new(&value()?)),
+            "-e" | "--ext" => query.extension = Some(value()?.trim_start_matches('.').to_string()),
+            "--min-size" => query.min_size = Some(parse_bytes(&value()?, "size")?),
+            "--max-size" => query.max_size = Some(parse_bytes(&value()?, "size")?),
+            "-x" | "--by-extension" => by_extension = true,
+            "-H" | "--human-readable" => human_readable = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            _ => return Err(format!("unknown option: {arg}"))
+        }
+    }
+
+    Ok(Command::Find(FindOptions { input, tree_options, query, by_extension, human_readable }))
+}
+
+fn parse_unknown_directory(value: &str) -> Result<UnknownDirectoryPolicy, String> {
+    match value {
+        "ignore" => Ok(UnknownDirectoryPolicy::Ignore),
+        "strict" => Ok(UnknownDirectoryPolicy::Strict),
+        "create" => Ok(UnknownDirectoryPolicy::Create),
+        other => Err(format!("invalid unknown directory policy: {other}"))
+    }
+}
+
+fn parse_conflict(value: &str) -> Result<ConflictPolicy, String> {
+    match value {
+        "warn" => Ok(ConflictPolicy::Warn),
+        "error" => Ok(ConflictPolicy::Error),
+        other => Err(format!("invalid conflict policy: {other}"))
+    }
+}
+
+fn parse_bytes(value: &str, name: &str) -> Result<u64, String> {
+    value.parse::<u64>().map_err(|_| format!("invalid {name}: {value}"))
+}
+
+fn parse_count(value: &str, name: &str) -> Result<usize, String> {
+    value.parse::<usize>().map_err(|_| format!("invalid {name}: {value}"))
+}
@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::{NodeRef, Tree};
+use crate::tree::join_path;
+
+/// Whether a changed path is a file or a directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Directory,
+    File
+}
+
+/// A file or directory that was added, removed or resized between two trees
+///
+/// Directories are also recorded when entries were added to or removed from them, even if their size
+/// stayed the same.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub path: String,
+    pub kind: NodeKind,
+    /// Size in the old tree, or `None` if the path was added
+    pub old_size: Option<u64>,
+    /// Size in the new tree, or `None` if the path was removed
+    pub new_size: Option<u64>
+}
+
+impl Change {
+    /// Change in size, counting a missing path as empty
+    pub fn delta(&self) -> i128 {
+        i128::from(self.new_size.unwrap_or_default()) - i128::from(self.old_size.unwrap_or_default())
+    }
+
+    fn name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    /// Path of the directory holding the changed path, or `None` for `/`
+    fn parent(&self) -> Option<&str> {
+        match self.path.rsplit_once('/')? {
+            ("", "") => None,
+            ("", _) => Some("/"),
+            (parent, _) => Some(parent)
+        }
+    }
+}
+
+/// Everything that changed between two rebuilt filesystems
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    /// Changed paths, each directory before the paths inside it, in listing order with added paths
+    /// after the paths listed in both trees
+    pub changes: Vec<Change>
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Compares the filesystems `old` and `new`, path by path
+///
+/// A file replaced by a directory of the same name, or the other way round, is recorded as removed and added.
+pub fn diff(old: &Tree, new: &Tree) -> Diff {
+    let mut changes = Vec::new();
+    let mut pending = vec![(Some(old.root()), Some(new.root()))];
+
+    while let Some((old_dir, new_dir)) = pending.pop() {
+        let Some(path) = old_dir.or(new_dir).map(|dir| dir.path()) else { continue };
+        let mut entries = Vec::new();
+        let mut subdirectories = Vec::new();
+
+        let old_children = old_dir.into_iter().flat_map(|dir| dir.children());
+        let added_children = new_dir.into_iter().flat_map(|dir| dir.children())
+            .filter(|child| old_dir.and_then(|dir| dir.child(child.name())).is_none());
+
+        for child in old_children {
+            match (child, new_dir.and_then(|dir| dir.child(child.name()))) {
+                (NodeRef::File(old_file), Some(NodeRef::File(new_file))) => if old_file.size() != new_file.size() {
+                    entries.push(file_change(&path, child.name(), Some(old_file.size()), Some(new_file.size())));
+                },
+                (NodeRef::Directory(old_child), Some(NodeRef::Directory(new_child))) => {
+                    subdirectories.push((Some(old_child), Some(new_child)));
+                },
+                (NodeRef::File(old_file), new_child) => {
+                    entries.push(file_change(&path, child.name(), Some(old_file.size()), None));
+                    subdirectories.extend(new_child.and_then(NodeRef::as_directory).map(|new_child| (None, Some(new_child))));
+                },
+                (NodeRef::Directory(old_child), new_child) => {
+                    subdirectories.push((Some(old_child), None));
+                    if let Some(NodeRef::File(new_file)) = new_child {
+                        entries.push(file_change(&path, child.name(), None, Some(new_file.size())));
+                    }
+                }
+            }
+        }
+
+        for child in added_children {
+            match child {
+                NodeRef::File(new_file) => entries.push(file_change(&path, child.name(), None, Some(new_file.size()))),
+                NodeRef::Directory(new_child) => subdirectories.push((None, Some(new_child)))
+            }
+        }
+
+        // a directory is recorded if it or its size changed, or if entries were added to or removed from it
+        let old_size = old_dir.map(|dir| dir.size());
+        let new_size = new_dir.map(|dir| dir.size());
+        let listing_changed = entries.iter().any(|entry| entry.old_size.is_none() || entry.new_size.is_none()) ||
+            subdirectories.iter().any(|(old_child, new_child)| old_child.is_none() || new_child.is_none());
+        if old_size != new_size || listing_changed {
+            changes.push(Change { path, kind: NodeKind::Directory, old_size, new_size });
+        }
+
+        changes.extend(entries);
+        pending.extend(subdirectories.into_iter().rev());
+    }
+
+    Diff { changes }
+}
+
+fn file_change(directory: &str, name: &str, old_size: Option<u64>, new_size: Option<u64>) -> Change {
+    Change { path: join_path(directory, name), kind: NodeKind::File, old_size, new_size }
+}
+
+/// A [`Diff`] written like a unified diff of every changed directory's listing, e.g.
+///
+/// ```text
+/// @@ -584 +684 @@ /a/e (+100)
+/// -584 i
+/// +684 i
+/// ```
+///
+/// Each changed directory gets a hunk, headed by its old and new size, with a line for every entry
+/// added to or removed from it and every file resized in it.
+pub struct UnifiedDiff<'a> {
+    diff: &'a Diff,
+    old_label: &'a str,
+    new_label: &'a str
+}
+
+impl<'a> UnifiedDiff<'a> {
+    /// Labels the old and new trees with `old_label` and `new_label`, e.g. the transcripts' file names
+    pub fn new(diff: &'a Diff, old_label: &'a str, new_label: &'a str) -> UnifiedDiff<'a> {
+        UnifiedDiff { diff, old_label, new_label }
+    }
+}
+
+impl fmt::Display for UnifiedDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "--- {}", self.old_label)?;
+        writeln!(f, "+++ {}", self.new_label)?;
+
+        let mut entries: HashMap<&str, Vec<&Change>> = HashMap::new();
+        for change in &self.diff.changes {
+            if let Some(parent) = change.parent() {
+                entries.entry(parent).or_default().push(change);
+            }
+        }
+
+        for dir in self.diff.changes.iter().filter(|change| change.kind == NodeKind::Directory) {
+            write!(f, "@@")?;
+            if let Some(old_size) = dir.old_size {
+                write!(f, " -{old_size}")?;
+            }
+            if let Some(new_size) = dir.new_size {
+                write!(f, " +{new_size}")?;
+            }
+            writeln!(f, " @@ {} ({:+})", dir.path, dir.delta())?;
+
+            for entry in entries.get(dir.path.as_str()).into_iter().flatten() {
+                let listing = |size: u64| match entry.kind {
+                    NodeKind::Directory => format!("dir {}", entry.name()),
+                    NodeKind::File => format!("{size} {}", entry.name())
+                };
+
+                // resized directories have a hunk of their own
+                if entry.kind == NodeKind::Directory && entry.old_size.is_some() && entry.new_size.is_some() {
+                    continue;
+                }
+                if let Some(old_size) = entry.old_size {
+                    writeln!(f, "-{}", listing(old_size))?;
+                }
+                if let Some(new_size) = entry.new_size {
+                    writeln!(f, "+{}", listing(new_size))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::EXAMPLE;
+    use crate::{Tree, TreeOptions};
+    use super::{diff, Change, NodeKind, UnifiedDiff};
+
+    fn trees(old: &str, new: &str) -> (Tree, Tree) {
+        (Tree::from_input(old, &TreeOptions::default()).unwrap(), Tree::from_input(new, &TreeOptions::default()).unwrap())
+    }
+
+    fn change(path: &str, kind: NodeKind, old_size: Option<u64>, new_size: Option<u64>) -> Change {
+        Change { path: String::from(path), kind, old_size, new_size }
+    }
+
+    #[test]
+    fn unchanged() {
+        let (old, new) = trees(EXAMPLE, EXAMPLE);
+
+        assert!(diff(&old, &new).is_empty())
+    }
+
+    #[test]
+    fn changes() {
+        let later = format!("{EXAMPLE}$ touch 684 /a/e/i\n$ cd /\n$ rm c.dat\n$ mkdir x\n$ touch 50 x/y\n");
+        let (old, new) = trees(EXAMPLE, &later);
+
+        assert_eq!(diff(&old, &new).changes, vec![
+            change("/", NodeKind::Directory, Some(48_381_165), Some(39_877_159)),
+            change("/c.dat", NodeKind::File, Some(8_504_156), None),
+            change("/a", NodeKind::Directory, Some(94_853), Some(94_953)),
+            change("/a/e", NodeKind::Directory, Some(584), Some(684)),
+            change("/a/e/i", NodeKind::File, Some(584), Some(684)),
+            change("/x", NodeKind::Directory, None, Some(50)),
+            change("/x/y", NodeKind::File, None, Some(50))
+        ])
+    }
+
+    #[test]
+    fn file_replaced_by_directory() {
+        let (old, new) = trees("$ cd /\n$ ls\n10 a\ndir b\n$ cd b\n$ ls\n5 c\n", "$ cd /\n$ ls\ndir a\n5 b\n");
+        let changes = diff(&old, &new).changes;
+
+        assert_eq!(changes, vec![
+            change("/", NodeKind::Directory, Some(15), Some(5)),
+            change("/a", NodeKind::File, Some(10), None),
+            change("/b", NodeKind::File, None, Some(5)),
+            change("/a", NodeKind::Directory, None, Some(0)),
+            change("/b", NodeKind::Directory, Some(5), None),
+            change("/b/c", NodeKind::File, Some(5), None)
+        ])
+    }
+
+    #[test]
+    fn unified_report() {
+        let later = format!("{EXAMPLE}$ touch 684 /a/e/i\n$ cd /\n$ rm c.dat\n$ mkdir x\n$ touch 50 x/y\n");
+        let (old, new) = trees(EXAMPLE, &later);
+        let diff = diff(&old, &new);
+
+        assert_eq!(UnifiedDiff::new(&diff, "before.txt", "after.txt").to_string(), "\
+--- before.txt
++++ after.txt
+@@ -48381165 +39877159 @@ / (-8504006)
+-8504156 c.dat
++dir x
+@@ -94853 +94953 @@ /a (+100)
+@@ -584 +684 @@ /a/e (+100)
+-584 i
++684 i
+@@ +50 @@ /x (+50)
++50 y
+")
+    }
+}
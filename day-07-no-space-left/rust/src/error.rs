@@ -0,0 +1,119 @@
+use std::fmt;
+use aoc_common::ParseError;
+
+/// Error raised while rebuilding a filesystem from a terminal transcript, or while measuring it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeError {
+    /// The line is not a command, a directory or a file
+    Parse(ParseError),
+    /// The command isn't one of `cd`, `ls`, `mkdir`, `touch` or `rm`
+    UnknownCommand { command: String },
+    /// `cd <name>` names a directory that hasn't been listed in the current directory
+    UnknownDirectory { name: String },
+    /// `cd <path>`, `rm <path>` or `touch <size> <path>` names an entry that doesn't exist
+    NoSuchEntry { path: String },
+    /// `rm` without `-r`, or `touch`, names a directory
+    IsADirectory { path: String },
+    /// `rm -r` names the current directory or one of its ancestors
+    InUse { path: String },
+    /// A path names `/`, `.` or `..` where a file or directory name is needed
+    InvalidPath { path: String },
+    /// `cd ..` was run in the root directory
+    CdAboveRoot,
+    /// A file was found where a directory was expected, e.g. `cd <file>`
+    NotADirectory { name: String },
+    /// A directory's size was read before the sizes were calculated
+    SizeNotComputed { name: String },
+    /// The total size of the directory at `path` doesn't fit in 64 bits
+    SizeOverflow { path: String },
+    /// The part 1 sum of the directories no larger than the small directory limit doesn't fit in 64 bits
+    SumOverflow,
+    /// Even deleting `/` doesn't leave `min_unused_space` of the `capacity` unused
+    NotEnoughSpace { capacity: u64, min_unused_space: u64 },
+    /// A node index doesn't point into the tree
+    NodeNotFound { index: usize },
+    /// An entry was listed again differently under [`ConflictPolicy::Error`](crate::ConflictPolicy::Error)
+    Conflict(Conflict)
+}
+
+/// An entry listed in a directory, as it was listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    Directory,
+    File { size: u64 }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Entry::Directory => write!(f, "directory"),
+            Entry::File { size } => write!(f, "file of size {size}")
+        }
+    }
+}
+
+/// A listing on transcript line `line` that contradicts an earlier listing of `path`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub line: usize,
+    pub path: String,
+    pub existing: Entry,
+    pub listed: Entry
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} listed as {} but was previously listed as {}", self.path, self.listed, self.existing)
+    }
+}
+
+impl TreeError {
+    /// Converts to the common error type, recording the transcript `line` the error was raised on
+    pub fn at_line(self, line: usize) -> aoc_common::Error {
+        match self {
+            TreeError::Parse(err) => aoc_common::Error::Parse(err.at_line(line)),
+            err => aoc_common::Error::day(err).at_line(line)
+        }
+    }
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TreeError::Parse(err) => write!(f, "{err}"),
+            TreeError::UnknownCommand { command } => write!(f, "unknown command: {command}"),
+            TreeError::UnknownDirectory { name } => write!(f, "cannot cd into unknown directory: {name}"),
+            TreeError::NoSuchEntry { path } => write!(f, "no such file or directory: {path}"),
+            TreeError::IsADirectory { path } => write!(f, "expected file but found directory: {path}"),
+            TreeError::InUse { path } => write!(f, "cannot remove {path}: it contains the current directory"),
+            TreeError::InvalidPath { path } => write!(f, "invalid path: {path}"),
+            TreeError::CdAboveRoot => write!(f, "cannot cd .. from root directory"),
+            TreeError::NotADirectory { name } => write!(f, "expected directory but found file: {name}"),
+            TreeError::SizeNotComputed { name } => write!(f, "size not computed for directory: {name}"),
+            TreeError::SizeOverflow { path } => write!(f, "size overflowed for directory: {path}"),
+            TreeError::SumOverflow => write!(f, "sum of small directory sizes overflowed"),
+            TreeError::NotEnoughSpace { capacity, min_unused_space } => {
+                write!(f, "no directory can be deleted to leave {min_unused_space} of {capacity} bytes unused")
+            },
+            TreeError::NodeNotFound { index } => write!(f, "node not found at index {index}"),
+            TreeError::Conflict(conflict) => write!(f, "conflicting listing: {conflict}")
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+impl From<ParseError> for TreeError {
+    fn from(err: ParseError) -> Self {
+        TreeError::Parse(err)
+    }
+}
+
+impl From<TreeError> for aoc_common::Error {
+    fn from(err: TreeError) -> Self {
+        match err {
+            TreeError::Parse(err) => aoc_common::Error::Parse(err),
+            err => aoc_common::Error::day(err)
+        }
+    }
+}
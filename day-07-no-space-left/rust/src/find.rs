@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+use crate::{DirectoryRef, FileRef, NodeRef};
+
+/// Conditions on files for [`find`], all of which must hold
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    pub glob: Option<Glob>,
+    /// Smallest size matched, inclusive
+    pub min_size: Option<u64>,
+    /// Largest size matched, inclusive
+    pub max_size: Option<u64>,
+    /// Extension without its dot, e.g. `log`
+    pub extension: Option<String>
+}
+
+impl Query {
+    pub fn matches(&self, file: &FileRef) -> bool {
+        self.min_size.is_none_or(|min_size| file.size() >= min_size) &&
+            self.max_size.is_none_or(|max_size| file.size() <= max_size) &&
+            self.extension.as_deref().is_none_or(|expected| extension(file.name()) == Some(expected)) &&
+            self.glob.as_ref().is_none_or(|glob| glob.matches_file(file))
+    }
+}
+
+/// A shell-style pattern, where `?` matches any one character and `*` any run of characters other than `/`,
+/// while `**` also matches across directories, e.g. `*.log` or `/a/**/h.*`
+///
+/// Patterns holding a `/` are matched against a file's whole path, and other patterns against its name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Glob {
+    tokens: Vec<Token>,
+    matches_path: bool
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Char(char),
+    /// `?`
+    AnyChar,
+    /// `*`
+    AnyName,
+    /// `**/`, matching nothing or any path ending in `/`
+    AnyDirectories,
+    /// `**` anywhere else
+    AnyPath
+}
+
+impl Glob {
+    pub fn new(pattern: &str) -> Glob {
+        let mut tokens = Vec::new();
+        let mut chars = pattern.chars().peekable();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                '?' => Token::AnyChar,
+                '*' if chars.next_if_eq(&'*').is_some() => match chars.next_if_eq(&'/') {
+                    Some(_) => Token::AnyDirectories,
+                    None => Token::AnyPath
+                },
+                '*' => Token::AnyName,
+                c => Token::Char(c)
+            };
+            tokens.push(token);
+        }
+
+        Glob { tokens, matches_path: pattern.contains('/') }
+    }
+
+    /// Whether the whole of `text` matches the pattern
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.chars().collect::<Vec<_>>();
+
+        // `next[j]` is whether the tokens after the current one match `text[j..]`
+        let mut next = vec![false; text.len() + 1];
+        next[text.len()] = true;
+        for token in self.tokens.iter().rev() {
+            let mut current = vec![false; text.len() + 1];
+            // whether some `/` at or after `j` is followed by a match of the tokens after this one
+            let mut directory_ahead = false;
+            for j in (0..=text.len()).rev() {
+                let c = text.get(j).copied();
+                current[j] = match token {
+                    Token::Char(expected) => c == Some(*expected) && next[j + 1],
+                    Token::AnyChar => c.is_some_and(|c| c != '/') && next[j + 1],
+                    Token::AnyName => next[j] || (c.is_some_and(|c| c != '/') && current[j + 1]),
+                    Token::AnyPath => next[j] || (c.is_some() && current[j + 1]),
+                    Token::AnyDirectories => {
+                        directory_ahead = directory_ahead || (c == Some('/') && next[j + 1]);
+                        next[j] || directory_ahead
+                    }
+                };
+            }
+            next = current;
+        }
+
+        next[0]
+    }
+
+    fn matches_file(&self, file: &FileRef) -> bool {
+        if self.matches_path {
+            self.matches(&file.path())
+        } else {
+            self.matches(file.name())
+        }
+    }
+}
+
+/// Number and total size of the files with one extension
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtensionTotal {
+    pub files: usize,
+    pub size: u64
+}
+
+/// Every file in `dir` or below it that `predicate` holds for, depth-first in listing order
+///
+/// A [`Query`] can be used as the predicate with `|file| query.matches(file)`.
+pub fn find<'a, F>(dir: DirectoryRef<'a>, predicate: F) -> impl Iterator<Item = FileRef<'a>>
+where F: Fn(&FileRef<'a>) -> bool {
+    dir.walk().filter_map(NodeRef::as_file).filter(move |file| predicate(file))
+}
+
+/// Number and total size of `files` by extension, where files without one are under `""`, e.g. for the files
+/// returned by [`find`]
+///
+/// Totals too large for a `u64` stop at `u64::MAX`.
+pub fn extension_totals<'a, I>(files: I) -> BTreeMap<String, ExtensionTotal>
+where I: IntoIterator<Item = FileRef<'a>> {
+    let mut totals = BTreeMap::<String, ExtensionTotal>::new();
+    for file in files {
+        let total = totals.entry(extension(file.name()).unwrap_or_default().to_string()).or_default();
+        total.files += 1;
+        total.size = total.size.saturating_add(file.size());
+    }
+
+    totals
+}
+
+/// The part of `name` after its last `.`, if it's neither first nor last, e.g. `lst` for `h.lst`
+fn extension(name: &str) -> Option<&str> {
+    match name.rsplit_once('.')? {
+        ("", _) | (_, "") => None,
+        (_, extension) => Some(extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::EXAMPLE;
+    use crate::{Tree, TreeOptions};
+    use super::{extension, extension_totals, find, ExtensionTotal, Glob, Query};
+
+    fn found(query: &Query) -> Vec<(String, u64)> {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        find(tree.root(), |file| query.matches(file)).map(|file| (file.path(), file.size())).collect()
+    }
+
+    #[test]
+    fn globs() {
+        let cases = [
+            ("*.log", "d.log", true),
+            ("*.log", "d.log.gz", false),
+            ("?.*", "h.lst", true),
+            ("*", "", true),
+            ("/a/*", "/a/e/i", false),
+            ("/a/**", "/a/e/i", true),
+            ("/a/**/h.*", "/a/h.lst", true),
+            ("/a/**/h.*", "/a/e/x/h.lst", true),
+            ("/a/**/h.*", "/ab/h.lst", false),
+            ("**.lst", "/a/e/h.lst", true),
+            ("a*b*c", "aXbYbZc", true),
+            ("a*b*c", "aXbYbZ", false)
+        ];
+
+        for (pattern, text, expected) in cases {
+            assert_eq!(Glob::new(pattern).matches(text), expected, "{pattern} on {text}");
+        }
+    }
+
+    #[test]
+    fn find_by_name() {
+        let query = Query { glob: Some(Glob::new("*.*")), ..Query::default() };
+        let paths = found(&query).into_iter().map(|(path, _)| path).collect::<Vec<_>>();
+
+        assert_eq!(paths, vec!["/a/h.lst", "/b.txt", "/c.dat", "/d/d.log", "/d/d.ext"])
+    }
+
+    #[test]
+    fn find_by_path() {
+        let query = Query { glob: Some(Glob::new("/a/**")), ..Query::default() };
+
+        assert_eq!(found(&query), vec![
+            (String::from("/a/e/i"), 584),
+            (String::from("/a/f"), 29_116),
+            (String::from("/a/g"), 2_557),
+            (String::from("/a/h.lst"), 62_596)
+        ])
+    }
+
+    #[test]
+    fn find_by_size_and_extension() {
+        let query = Query { min_size: Some(5_000_000), max_size: Some(8_100_000), ..Query::default() };
+        let by_extension = Query { extension: Some(String::from("log")), ..Query::default() };
+
+        assert_eq!(found(&query), vec![(String::from("/d/d.log"), 8_033_020), (String::from("/d/d.ext"), 5_626_152), (String::from("/d/k"), 7_214_296)]);
+        assert_eq!(found(&by_extension), vec![(String::from("/d/d.log"), 8_033_020)])
+    }
+
+    #[test]
+    fn totals_by_extension() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let totals = extension_totals(find(tree.root(), |_| true));
+
+        assert_eq!(totals.keys().collect::<Vec<_>>(), vec!["", "dat", "ext", "log", "lst", "txt"]);
+        assert_eq!(totals[""], ExtensionTotal { files: 5, size: 584 + 29_116 + 2_557 + 4_060_174 + 7_214_296 })
+    }
+
+    #[test]
+    fn totals_saturate() {
+        let tree = Tree::from_input(&format!("$ cd /\n$ ls\n{} x.img\n", u64::MAX / 2 + 1), &TreeOptions::default()).unwrap();
+        // a tree's sizes always fit, but the same files can be counted more than once
+        let totals = extension_totals(find(tree.root(), |_| true).chain(find(tree.root(), |_| true)));
+
+        assert_eq!(totals["img"], ExtensionTotal { files: 2, size: u64::MAX })
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!([extension("h.lst"), extension("a.tar.gz"), extension(".bashrc"), extension("x."), extension("k")],
+            [Some("lst"), Some("gz"), None, None, None])
+    }
+}
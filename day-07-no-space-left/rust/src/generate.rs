@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+/// Shape of the filesystems built by [`generate`]
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    /// Number of directories below `/`
+    pub directories: usize,
+    pub files: usize,
+    /// Files are between 1 and `max_file_size` bytes, which is lowered to `u64::MAX / files` so that the total
+    /// size fits in 64 bits
+    pub max_file_size: u64,
+    /// Number of directories listed a second time, after every directory has been listed once
+    pub revisits: usize
+}
+
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        GeneratorOptions { directories: 50, files: 200, max_file_size: 1_000_000, revisits: 10 }
+    }
+}
+
+/// A random filesystem, along with a transcript that lists it
+#[derive(Debug)]
+pub struct Generated {
+    pub transcript: String,
+    /// Total size of every directory, by path
+    pub directory_sizes: BTreeMap<String, u64>
+}
+
+/// Builds a random filesystem from `seed` and a transcript that lists it, moving between directories
+/// with a mix of `cd /` jumps and `cd ..` chains
+///
+/// The same seed and options always give the same transcript.
+pub fn generate(seed: u64, options: &GeneratorOptions) -> Generated {
+    let mut rng = Rng::new(seed);
+
+    // every directory's parent is created before it, so the root is at 0 and parents have lower indices
+    let mut dirs = vec![GenDirectory { name: String::new(), parent: None, children: Vec::new(), entries: Vec::new() }];
+    for i in 1..=options.directories {
+        let parent = rng.below(i);
+        dirs[parent].children.push(i);
+        dirs[parent].entries.push(format!("dir d{i}"));
+        dirs.push(GenDirectory { name: format!("d{i}"), parent: Some(parent), children: Vec::new(), entries: Vec::new() });
+    }
+
+    let max_file_size = options.max_file_size.clamp(1, u64::MAX / options.files.max(1) as u64);
+    let mut sizes = vec![0; dirs.len()];
+    for i in 0..options.files {
+        let dir = rng.below(dirs.len());
+        let size = 1 + rng.below_u64(max_file_size);
+        dirs[dir].entries.push(format!("{size} f{i}.{}", ["txt", "dat", "log", "bin"][rng.below(4)]));
+        sizes[dir] += size;
+    }
+
+    for i in (1..dirs.len()).rev() {
+        if let Some(parent) = dirs[i].parent {
+            sizes[parent] += sizes[i];
+        }
+    }
+
+    let directory_sizes = (0..dirs.len()).map(|i| path_of(&dirs, i)).zip(sizes).collect();
+
+    // list each directory after its parent, picking at random among those whose parent is listed
+    let mut order = Vec::with_capacity(dirs.len() + options.revisits);
+    let mut ready = vec![0];
+    while !ready.is_empty() {
+        let dir = ready.swap_remove(rng.below(ready.len()));
+        order.push(dir);
+        ready.extend(&dirs[dir].children);
+    }
+
+    for _ in 0..options.revisits {
+        order.push(rng.below(dirs.len()));
+    }
+
+    let mut transcript = String::from("$ cd /\n");
+    let mut current = 0;
+    for dir in order {
+        cd(&mut transcript, &dirs, current, dir, &mut rng);
+        current = dir;
+
+        transcript.push_str("$ ls\n");
+        let mut entries = dirs[dir].entries.clone();
+        rng.shuffle(&mut entries);
+        for entry in entries {
+            transcript.push_str(&entry);
+            transcript.push('\n');
+        }
+    }
+
+    Generated { transcript, directory_sizes }
+}
+
+struct GenDirectory {
+    name: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// Lines listing the directory's contents
+    entries: Vec<String>
+}
+
+/// Indices of the directories from the root down to the directory at `index`
+fn ancestors(dirs: &[GenDirectory], index: usize) -> Vec<usize> {
+    let mut ancestors = vec![index];
+    let mut current = index;
+    while let Some(parent) = dirs[current].parent {
+        ancestors.push(parent);
+        current = parent;
+    }
+
+    ancestors.reverse();
+    ancestors
+}
+
+fn path_of(dirs: &[GenDirectory], index: usize) -> String {
+    match index {
+        0 => String::from("/"),
+        _ => ancestors(dirs, index).iter().skip(1).map(|i| format!("/{}", dirs[*i].name)).collect()
+    }
+}
+
+/// Writes the commands moving from directory `from` to directory `to`
+fn cd(transcript: &mut String, dirs: &[GenDirectory], from: usize, to: usize, rng: &mut Rng) {
+    let from_path = ancestors(dirs, from);
+    let to_path = ancestors(dirs, to);
+    let mut common = from_path.iter().zip(&to_path).take_while(|(a, b)| a == b).count();
+
+    // sometimes jump back to the root even when going up would be shorter
+    if common < from_path.len() && rng.below(3) == 0 {
+        transcript.push_str("$ cd /\n");
+        common = 1;
+    } else {
+        for _ in common..from_path.len() {
+            transcript.push_str("$ cd ..\n");
+        }
+    }
+
+    for dir in &to_path[common..] {
+        transcript.push_str(&format!("$ cd {}\n", dirs[*dir].name));
+    }
+}
+
+/// SplitMix64, which is plenty for shuffling test data
+struct Rng {
+    state: u64
+}
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below_u64(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        self.below_u64(bound as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use proptest::prelude::*;
+    use crate::{run_lines, NodeRef, Tree, TreeOptions};
+    use super::{generate, GeneratorOptions};
+
+    #[test]
+    fn same_seed_same_transcript() {
+        let options = GeneratorOptions::default();
+
+        assert_eq!(generate(7, &options).transcript, generate(7, &options).transcript)
+    }
+
+    #[test]
+    fn transcript_moves() {
+        let generated = generate(1, &GeneratorOptions { directories: 200, ..GeneratorOptions::default() });
+        let lines = generated.transcript.lines().collect::<Vec<_>>();
+
+        assert!(lines.iter().skip(1).any(|line| *line == "$ cd /"));
+        assert!(lines.windows(2).any(|pair| pair == ["$ cd ..", "$ cd .."]))
+    }
+
+    #[test]
+    fn largest_file_size() {
+        let generated = generate(3, &GeneratorOptions { max_file_size: u64::MAX, ..GeneratorOptions::default() });
+        let tree = Tree::from_input(&generated.transcript, &TreeOptions::default()).unwrap();
+
+        assert_eq!(Some(&tree.total_size()), generated.directory_sizes.get("/"))
+    }
+
+    proptest! {
+        #[test]
+        fn sizes_match_ground_truth(
+            seed in any::<u64>(),
+            directories in 0usize..60,
+            files in 0usize..200,
+            max_file_size in 1u64..=u64::MAX,
+            revisits in 0usize..20
+        ) {
+            let generated = generate(seed, &GeneratorOptions { directories, files, max_file_size, revisits });
+            let tree = Tree::from_input(&generated.transcript, &TreeOptions::default()).unwrap();
+
+            let sizes = tree.root().walk()
+                .filter_map(NodeRef::as_directory)
+                .map(|dir| (dir.path(), dir.size()))
+                .collect::<BTreeMap<_, _>>();
+            prop_assert_eq!(sizes, generated.directory_sizes);
+            prop_assert!(tree.warnings().is_empty());
+        }
+
+        #[test]
+        fn results_match_ground_truth(seed in any::<u64>()) {
+            let generated = generate(seed, &GeneratorOptions { max_file_size: 100_000, ..GeneratorOptions::default() });
+            let results = run_lines(&generated.transcript).unwrap();
+
+            let sum_1 = generated.directory_sizes.values().filter(|size| **size <= 100_000).sum::<u64>();
+            prop_assert_eq!(results.sum_1, sum_1);
+        }
+    }
+}
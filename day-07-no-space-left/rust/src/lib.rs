@@ -1,279 +1,189 @@
-use std::error::Error;
-use std::fs;
-use std::io::{self, BufRead};
+use std::io::BufRead;
 use std::path::Path;
-
-// TODO: use proper errors instead of panic
+use aoc_common::{open, Solution};
+
+pub use diff::{diff, Change, Diff, NodeKind, UnifiedDiff};
+pub use error::{Conflict, Entry, TreeError};
+pub use find::{extension_totals, find, ExtensionTotal, Glob, Query};
+pub use generate::{generate, Generated, GeneratorOptions};
+pub use plan::{plan_deletions, Plan, PlanOptions};
+pub use render::{human_readable, DiskUsage, LargestDirectories, RenderOptions, TreeListing};
+pub use scan::{scan, Scan, ScanOptions, SymlinkPolicy, Traversal};
+pub use timeline::{Snapshot, Timeline};
+pub use tree::{DirectoryRef, FileRef, NodeRef, Tree, Walk};
+
+mod diff;
+mod error;
+mod find;
+mod generate;
+mod plan;
+mod render;
+mod scan;
+mod timeline;
+mod tree;
 
 /// Solves Day 7 from a file path `filename`
-pub fn run<P>(filename: P) -> Result<Results, Box<dyn Error>>
+pub fn run<P>(filename: P) -> aoc_common::Result<Results>
 where P: AsRef<Path> {
-    let mut tree = Tree::new();
-    let lines = read_lines(filename)?;
-    for l in lines {
-        let line = l?;
-        tree.handle_line(&line)?;
-    }
-
-    Ok(Results::from_tree(tree)?)
+    Results::from_file(filename)
 }
 
-/// Solves Day 7 from a string slice `input`
-pub fn run_lines(input: &str) -> Result<Results, Box<dyn Error>> {
-    let mut tree = Tree::new();
-    for line in input.lines() {
-        tree.handle_line(line)?;
-    }
+/// Solves Day 7 from a file path `filename`, building the tree according to `options`
+/// and measuring it against `parameters`
+pub fn run_with<P>(filename: P, options: &TreeOptions, parameters: &Parameters) -> aoc_common::Result<Results>
+where P: AsRef<Path> {
+    Results::from_reader_with(open(filename)?, options, parameters)
+}
 
-    Ok(Results::from_tree(tree)?)
+/// Solves Day 7 from a transcript read from `reader` as it arrives, e.g. stdin or a pipe, building the tree
+/// according to `options` and measuring it against `parameters`
+pub fn run_reader_with<R>(reader: R, options: &TreeOptions, parameters: &Parameters) -> aoc_common::Result<Results>
+where R: BufRead {
+    Results::from_reader_with(reader, options, parameters)
 }
 
-#[derive(Debug)]
-pub struct Results {
-    sum_1: i32,
-    size_2: i32
+/// Solves Day 7 from a string slice `input`
+pub fn run_lines(input: &str) -> aoc_common::Result<Results> {
+    run_lines_with(input, &TreeOptions::default(), &Parameters::default())
 }
 
-impl Results {
-    const CAPACITY: i32 = 70_000_000;
-    const MIN_UNUSED_SPACE: i32 = 30_000_000;
+/// Solves Day 7 from a string slice `input`, building the tree according to `options`
+/// and measuring it against `parameters`
+pub fn run_lines_with(input: &str, options: &TreeOptions, parameters: &Parameters) -> aoc_common::Result<Results> {
+    let tree = Tree::from_input(input, options)?;
 
-    fn from_tree(mut tree: Tree) -> Result<Results, Box<dyn Error>> {
-        fn unused_space(total_size: i32, dir_size: i32) -> i32 {
-            Results::CAPACITY - (total_size - dir_size)
-        }
+    Ok(Results::from_tree(&tree, parameters)?)
+}
 
-        let mut sum_1 = 0;
-        let total_size = calc_directory_sizes(&mut tree.nodes)?;
-        let mut smallest_dir_size_for_update = total_size;
-
-        for node in tree.nodes.as_mut_slice() {
-            match node {
-                Node::Directory(dir) => {
-                    let size = dir.get_size()?;
-
-                    if size <= 100_000 {
-                        sum_1 += size;
-                    }
-
-                    if unused_space(total_size, size) >= Results::MIN_UNUSED_SPACE &&
-                        size < smallest_dir_size_for_update {
-                        smallest_dir_size_for_update = size;
-                    }
-                },
-                _ => continue
-            };
-        }
+/// Disk and update sizes the answers are measured against, in bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameters {
+    /// Total size of the disk
+    pub capacity: u64,
+    /// Unused space needed to run the update, for part 2
+    pub min_unused_space: u64,
+    /// Largest directory counted in the part 1 sum
+    pub small_directory_limit: u64
+}
 
-        Ok(Results { sum_1, size_2: smallest_dir_size_for_update })
+impl Default for Parameters {
+    fn default() -> Self {
+        Parameters { capacity: 70_000_000, min_unused_space: 30_000_000, small_directory_limit: 100_000 }
     }
 }
 
-struct Tree {
-    nodes: Vec<Node>,
-    current_index: usize
+/// Options for rebuilding a filesystem from a transcript
+#[derive(Debug, Clone, Default)]
+pub struct TreeOptions {
+    pub unknown_directory: UnknownDirectoryPolicy,
+    pub conflict: ConflictPolicy
 }
 
-impl Tree {
-    const ROOT_INDEX: usize = 0;
-
-    fn new() -> Tree {
-        let root_dir = Directory::new("root", None);
-        let root = Node::Directory(root_dir);
-        let nodes = vec![root];
-        Tree { nodes, current_index: Tree::ROOT_INDEX }
-    }
+/// How `cd <name>` treats a directory that was never listed in the current directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownDirectoryPolicy {
+    /// Stay in the current directory, as transcripts have always been read
+    #[default]
+    Ignore,
+    /// Fail with [`TreeError::UnknownDirectory`]
+    Strict,
+    /// Create the directory in the current directory, then enter it
+    Create
+}
 
-    fn current_directory(&self) -> Result<&Directory, Box<dyn Error>> {
-        let node = self.nodes.get(self.current_index).ok_or("bad index")?;
+/// How a listing that contradicts an earlier listing of the same entry is treated,
+/// e.g. a file listed again with a different size
+///
+/// Entries listed again unchanged are always ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the earlier listing and record the conflict as a warning
+    #[default]
+    Warn,
+    /// Fail with [`TreeError::Conflict`]
+    Error
+}
 
-        match node {
-            Node::Directory(dir) => Ok(dir),
-            Node::File(_) => panic!("expected directory but found file")
-        }
-    }
+#[derive(Debug)]
+pub struct Results {
+    sum_1: u64,
+    size_2: u64,
+    warnings: Vec<Conflict>
+}
 
-    fn current_directory_mut(&mut self) -> Result<&mut Directory, Box<dyn Error>> {
-        let node = self.nodes.get_mut(self.current_index).ok_or("bad index")?;
+impl Results {
+    /// Measures the rebuilt filesystem `tree` against `parameters`
+    pub fn from_tree(tree: &Tree, parameters: &Parameters) -> Result<Results, TreeError> {
+        let mut sum_1: u64 = 0;
+        let total_size = tree.total_size();
+        let mut smallest_dir_size_for_update = None;
 
-        match node {
-            Node::Directory(dir) => Ok(dir),
-            Node::File(_) => panic!("expected directory but found file")
-        }
-    }
+        // deleting a directory at least this large leaves enough unused space for the update
+        let min_dir_size_for_update = total_size.checked_add(parameters.min_unused_space)
+            .map(|size| size.saturating_sub(parameters.capacity));
 
-    fn handle_line(&mut self, line: &str) -> Result<(), Box<dyn Error>> {
-        let (first, rest) = line.split_once(' ').ok_or_else(|| format!("invalid line: {line}"))?;
+        for dir in tree.root().walk().filter_map(NodeRef::as_directory) {
+            let size = dir.size();
 
-        match first {
-            "$" => self.handle_command(rest)?,
-            "dir" => self.handle_dir(rest)?,
-            _ => self.handle_file(first, rest)?
-        }
+            if size <= parameters.small_directory_limit {
+                sum_1 = sum_1.checked_add(size).ok_or(TreeError::SumOverflow)?;
+            }
 
-        Ok(())
-    }
-
-    fn handle_command(&mut self, command: &str) -> Result<(), Box<dyn Error>> {
-        let mut tokens = command.split(' ');
-        let cmd = tokens.next().ok_or("invalid command")?;
-        match cmd {
-            "ls" => return Ok(()),
-
-            "cd" => {
-                let argument = tokens.next().ok_or_else(|| format!("missing argument for: {command}"))?;
-                match argument {
-                    "/" => self.current_index = 0,
-
-                    ".." => {
-                        let current_directory = self.current_directory()?;
-                        self.current_index = current_directory.parent_index.ok_or("cannot cd .. from root directory")?;
-                    },
-
-                    _ => {
-                        let current_directory = self.current_directory()?;
-                        for child_index in &current_directory.children_indices {
-                            match self.nodes.get(*child_index).ok_or("failed to get child")? {
-                                Node::Directory(child_dir) if child_dir.name == argument => {
-                                    self.current_index = *child_index;
-                                    break;
-                                },
-                                _ => continue
-                            };
-                        }
-                    }
-                };
-            },
-
-            _ => panic!("invalid command: {command}")
+            if min_dir_size_for_update.is_some_and(|min_size| size >= min_size) &&
+                smallest_dir_size_for_update.is_none_or(|smallest| size < smallest) {
+                smallest_dir_size_for_update = Some(size);
+            }
         }
 
-        Ok(())
-    }
-
-    fn handle_dir(&mut self, name: &str) -> Result<(), Box<dyn Error>> {
-        let dir = Directory::new(name, Some(self.current_index));
-
-        self.push(Node::Directory(dir))?;
+        let size_2 = smallest_dir_size_for_update.ok_or(TreeError::NotEnoughSpace {
+            capacity: parameters.capacity,
+            min_unused_space: parameters.min_unused_space
+        })?;
 
-        Ok(())
+        Ok(Results { sum_1, size_2, warnings: tree.warnings().to_vec() })
     }
 
-    fn handle_file(&mut self, size_str: &str, name: &str) -> Result<(), Box<dyn Error>> {
-        let size = size_str.parse::<i32>()?;
-        let file = File { name: name.to_string(), size };
-
-        self.push(Node::File(file))?;
-
-        Ok(())
+    /// Conflicting listings that were ignored under [`ConflictPolicy::Warn`]
+    pub fn warnings(&self) -> &[Conflict] {
+        &self.warnings
     }
 
-    fn push(&mut self, node: Node) -> Result<(), Box<dyn Error>> {
-        let index = self.nodes.len();
-        self.nodes.push(node);
-        let current_directory = self.current_directory_mut()?;
-        current_directory.children_indices.push(index);
+    fn from_reader_with<R>(reader: R, options: &TreeOptions, parameters: &Parameters) -> aoc_common::Result<Results>
+    where R: BufRead {
+        let tree = Tree::from_reader(reader, options)?;
 
-        Ok(())
+        Ok(Results::from_tree(&tree, parameters)?)
     }
 }
 
-enum Node {
-    Directory(Directory),
-    File(File)
-}
-
-struct Directory {
-    name: String,
-    parent_index: Option<usize>,
-    children_indices: Vec<usize>,
-    size: Option<i32>
-}
+impl Solution for Results {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "No Space Left On Device";
 
-impl Directory {
-    fn new(name: &str, parent_index: Option<usize>) -> Directory {
-        Directory {
-            name: name.to_string(),
-            parent_index,
-            children_indices: Vec::new(),
-            size: None
-        }
-    }
+    type Part1 = u64;
+    type Part2 = u64;
 
-    fn get_size(&self) -> Result<i32, Box<dyn Error>> {
-        match self.size {
-            Some(size) => Ok(size),
-            None => panic!("size not initialized for {} - do you need to call calc_directory_sizes", self.name)
-        }
+    fn from_reader<R>(reader: R) -> aoc_common::Result<Self>
+    where R: BufRead {
+        Results::from_reader_with(reader, &TreeOptions::default(), &Parameters::default())
     }
-}
 
-struct File {
-    name: String,
-    size: i32
-}
-
-// I'm not totally happy with this approach since it needs a temporary Vec, but it gets around the borrow checker
-fn calc_directory_sizes(nodes: &mut [Node]) -> Result<i32, Box<dyn Error>> {
-    let root_index = 0;
-    let sizes: &mut Vec<Option<i32>> = &mut Vec::with_capacity(nodes.len());
-    for _ in 0..nodes.len() {
-        sizes.push(None);
+    fn part_1(&self) -> u64 {
+        self.sum_1
     }
 
-    let size = calc_directory_size_core(nodes, root_index, sizes)?;
-
-    for i in 0..nodes.len() {
-        match nodes.get_mut(i).ok_or_else(|| format!("failed to get node at {i}"))? {
-            Node::Directory(dir) => {
-                let dir_size = sizes[i].ok_or_else(|| format!("failed to get size for {}", dir.name))?;
-                dir.size = Some(dir_size);
-            },
-
-            _ => continue
-        }
+    fn part_2(&self) -> u64 {
+        self.size_2
     }
-
-    Ok(size)
-}
-
-fn calc_directory_size_core(nodes: &[Node], index: usize, sizes: &mut [Option<i32>]) -> Result<i32, Box<dyn Error>> {
-    if let Some(size) = sizes[index] {
-        return Ok(size);
-    }
-
-    let mut size = 0;
-    let node = nodes.get(index).ok_or_else(|| format!("failed to get node at {index}"))?;
-    match node {
-        Node::Directory(dir) => {
-            for child_index in &dir.children_indices {
-                let child = nodes.get(*child_index).ok_or_else(|| format!("failed to get node at {index}"))?;
-                size += match child {
-                    Node::Directory(_) => calc_directory_size_core(nodes, *child_index, sizes)?,
-                    Node::File(child_file) => child_file.size
-                };
-            }
-        },
-
-        _ => panic!("unexpected node type at {index}")
-    };
-
-    sizes[index] = Some(size);
-    Ok(size)
-}
-
-// from https://doc.rust-lang.org/rust-by-example/std_misc/file/read_lines.html
-fn read_lines<P>(filename: P) -> io::Result<io::Lines<io::BufReader<fs::File>>>
-where P: AsRef<Path> {
-    let file = fs::File::open(filename)?;
-    Ok(io::BufReader::new(file).lines())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::run_lines;
+    use std::io::{BufReader, Read};
+    use aoc_common::{Error, Solution};
+    use crate::{run_lines, run_lines_with, run_reader_with, Conflict, ConflictPolicy, Entry, Parameters, Results, Tree, TreeError, TreeOptions, UnknownDirectoryPolicy};
 
-    const EXAMPLE: &'static str = "\
+    pub(crate) const EXAMPLE: &str = "\
 $ cd /
 $ ls
 dir a
@@ -312,4 +222,246 @@ fn part_2() {
 
         assert_eq!(results.size_2, 24_933_642)
     }
+
+    #[test]
+    fn custom_parameters() {
+        // / = 48_381_165, so a = 94_853 is the smallest directory that leaves 200_000 unused
+        let parameters = Parameters { capacity: 48_500_000, min_unused_space: 200_000, small_directory_limit: 1_000 };
+        let results = run_lines_with(EXAMPLE, &TreeOptions::default(), &parameters).unwrap();
+
+        assert_eq!((results.sum_1, results.size_2), (584, 94_853))
+    }
+
+    #[test]
+    fn update_larger_than_disk() {
+        let parameters = Parameters { capacity: 10_000_000, min_unused_space: 20_000_000, ..Parameters::default() };
+        let err = run_lines_with(EXAMPLE, &TreeOptions::default(), &parameters).unwrap_err();
+
+        assert_eq!(err.to_string(), "failed to solve: no directory can be deleted to leave 20000000 of 10000000 bytes unused")
+    }
+
+    #[test]
+    fn from_reader() {
+        let results = Results::from_reader(EXAMPLE.as_bytes()).unwrap();
+
+        assert_eq!((results.part_1(), results.part_2()), (95_437, 24_933_642))
+    }
+
+    /// Reader returning at most `chunk` bytes per read, so lines arrive split across reads
+    struct ChunkedReader<'a> {
+        data: &'a [u8],
+        chunk: usize
+    }
+
+    impl Read for ChunkedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn chunked_reader() {
+        let crlf = EXAMPLE.replace('\n', "\r\n");
+        for input in [EXAMPLE, &crlf, crlf.trim_end_matches('\n')] {
+            for chunk in 1..=7 {
+                let reader = BufReader::new(ChunkedReader { data: input.as_bytes(), chunk });
+                let results = run_reader_with(reader, &TreeOptions::default(), &Parameters::default()).unwrap();
+
+                assert_eq!((results.part_1(), results.part_2()), (95_437, 24_933_642), "chunks of {chunk}");
+            }
+        }
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let results = run_lines(&EXAMPLE.replace('\n', "\r\n")).unwrap();
+        let err = run_lines("$ cd /\r\n$ ls\r\n12x b.txt\r\n").unwrap_err();
+
+        assert_eq!((results.part_1(), results.part_2()), (95_437, 24_933_642));
+        assert_eq!(err.to_string(), run_lines("$ cd /\n$ ls\n12x b.txt\n").unwrap_err().to_string())
+    }
+
+    #[test]
+    fn parse_error_file_size() {
+        let err = match run_lines("$ cd /\n$ ls\n12x b.txt\n") {
+            Err(Error::Parse(err)) => err,
+            other => panic!("expected parse error, got {other:?}")
+        };
+
+        assert_eq!((err.day, err.line, err.span.clone(), err.found()), (Some(7), Some(3), 0..3, "12x"))
+    }
+
+    #[test]
+    fn parse_error_missing_cd_argument() {
+        let err = match run_lines("$ cd /\n$ cd\n") {
+            Err(Error::Parse(err)) => err,
+            other => panic!("expected parse error, got {other:?}")
+        };
+
+        assert_eq!((err.line, err.span), (Some(2), 4..4))
+    }
+
+    const UNLISTED: &str = "\
+$ cd /
+$ cd a
+$ ls
+100 x
+$ cd /
+$ ls
+50 y
+";
+
+    #[test]
+    fn unknown_directory_strict() {
+        let options = TreeOptions { unknown_directory: UnknownDirectoryPolicy::Strict, ..TreeOptions::default() };
+        let err = run_lines_with(UNLISTED, &options, &Parameters::default()).unwrap_err();
+
+        assert_eq!(err.to_string(), "failed to solve: line 2: cannot cd into unknown directory: a")
+    }
+
+    #[test]
+    fn unknown_directory_create() {
+        let options = TreeOptions { unknown_directory: UnknownDirectoryPolicy::Create, ..TreeOptions::default() };
+        let results = run_lines_with(UNLISTED, &options, &Parameters::default()).unwrap();
+
+        // a = 100, / = 150
+        assert_eq!(results.sum_1, 250)
+    }
+
+    #[test]
+    fn unknown_directory_ignore() {
+        let options = TreeOptions { unknown_directory: UnknownDirectoryPolicy::Ignore, ..TreeOptions::default() };
+        let results = run_lines_with(UNLISTED, &options, &Parameters::default()).unwrap();
+
+        // x lands in / since the cd is ignored
+        assert_eq!(results.sum_1, 150)
+    }
+
+    #[test]
+    fn repeated_ls() {
+        let repeated = EXAMPLE.replace("$ cd d\n", "$ cd d\n$ ls\n4060174 j\n$ cd ..\n$ ls\ndir a\ndir d\n14848514 b.txt\n$ cd d\n");
+        let results = run_lines(&repeated).unwrap();
+
+        assert_eq!((results.sum_1, results.size_2, results.warnings().len()), (95_437, 24_933_642, 0))
+    }
+
+    const RESIZED: &str = "\
+$ cd /
+$ ls
+dir a
+100 b
+$ ls
+200 b
+100 a
+";
+
+    #[test]
+    fn conflict_warn() {
+        let results = run_lines(RESIZED).unwrap();
+
+        // the first listing of b is kept, so / = 100 and a = 0
+        assert_eq!(results.sum_1, 100);
+        assert_eq!(results.warnings(), &[
+            Conflict { line: 6, path: String::from("/b"), existing: Entry::File { size: 100 }, listed: Entry::File { size: 200 } },
+            Conflict { line: 7, path: String::from("/a"), existing: Entry::Directory, listed: Entry::File { size: 100 } }
+        ])
+    }
+
+    #[test]
+    fn conflict_error() {
+        let options = TreeOptions { conflict: ConflictPolicy::Error, ..TreeOptions::default() };
+        let err = run_lines_with(RESIZED, &options, &Parameters::default()).unwrap_err();
+
+        assert_eq!(err.to_string(), "failed to solve: line 6: conflicting listing: /b listed as file of size 200 but was previously listed as file of size 100")
+    }
+
+    const TERABYTE: u64 = 1_000_000_000_000;
+
+    /// Transcript of `/` holding `count` directories, each holding one file of `size`, and one small file
+    fn wide_transcript(count: usize, size: u64) -> String {
+        let mut transcript = String::from("$ cd /\n$ ls\n1000 small.txt\n");
+        for i in 0..count {
+            transcript.push_str(&format!("dir d{i}\n"));
+        }
+
+        for i in 0..count {
+            transcript.push_str(&format!("$ cd d{i}\n$ ls\n{size} disk.img\n$ cd ..\n"));
+        }
+
+        transcript
+    }
+
+    #[test]
+    fn multi_terabyte_sizes() {
+        let results = run_lines(&wide_transcript(1_000, 4 * TERABYTE)).unwrap();
+
+        // only / frees enough space, since every other directory leaves more than the capacity in use
+        assert_eq!((results.sum_1, results.size_2), (0, 4_000 * TERABYTE + 1_000))
+    }
+
+    #[test]
+    fn multi_terabyte_nested_sizes() {
+        let transcript = "$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\ndir b\n3000000000000 a.img\n$ cd b\n$ ls\n50000 b.txt\n";
+        let results = run_lines(transcript).unwrap();
+
+        assert_eq!((results.sum_1, results.size_2), (50_000, 3 * TERABYTE + 50_000))
+    }
+
+    #[test]
+    fn size_overflow_in_parent() {
+        let transcript = format!("$ cd /\n$ ls\n1 x\ndir a\n$ cd a\n$ ls\n{} y\n", u64::MAX);
+        let err = run_lines(&transcript).unwrap_err();
+
+        assert_eq!(err.to_string(), "failed to solve: size overflowed for directory: /")
+    }
+
+    #[test]
+    fn small_directory_sum_overflow() {
+        // each of a and b fits, as does / = 2 * (u64::MAX / 4 + 1), but counting / as well overflows the sum
+        let size = u64::MAX / 4 + 1;
+        let transcript = format!("$ cd /\n$ ls\ndir a\ndir b\n$ cd a\n$ ls\n{size} x\n$ cd ..\n$ cd b\n$ ls\n{size} y\n");
+        let parameters = Parameters { small_directory_limit: u64::MAX, ..Parameters::default() };
+        let err = run_lines_with(&transcript, &TreeOptions::default(), &parameters).unwrap_err();
+
+        assert_eq!(err.day_error::<TreeError>(), Some(&TreeError::SumOverflow))
+    }
+
+    #[test]
+    fn hostile_lines_do_not_panic() {
+        let lines = ["", "$", "$ ", "$ cd", "$ cd ", "$ ls ls", "dir", "dir ", "99999999999 a", "a b c", "$ cd ..", "\u{1F4A3} \u{1F4A3}"];
+        for first in lines {
+            for second in lines {
+                let _ = run_lines(&format!("{first}\n{second}\n$ cd /\n{second}\n{first}"));
+            }
+        }
+    }
+
+    #[test]
+    fn error_line_number() {
+        let options = TreeOptions { unknown_directory: UnknownDirectoryPolicy::Strict, ..TreeOptions::default() };
+        let err = run_lines_with("$ cd /\n$ ls\ndir a\n$ cd b\n", &options, &Parameters::default()).unwrap_err();
+
+        assert!(matches!(err, Error::Day { line: Some(4), .. }));
+        assert_eq!(err.day_error::<TreeError>(), Some(&TreeError::UnknownDirectory { name: String::from("b") }));
+        assert_eq!(err.to_string(), "failed to solve: line 4: cannot cd into unknown directory: b")
+    }
+
+    #[test]
+    fn errors_keep_their_variants() {
+        let cd_above_root = run_lines("$ cd /\n$ cd ..\n").unwrap_err();
+        let unknown_command = Tree::from_input("$ cd /\n$ pwd\n", &TreeOptions::default()).err().unwrap();
+        let no_such_entry = Tree::from_reader("$ cd /\n$ rm x\n".as_bytes(), &TreeOptions::default()).err().unwrap();
+        let parameters = Parameters { capacity: 10_000_000, min_unused_space: 20_000_000, ..Parameters::default() };
+        let not_enough_space = run_lines_with(EXAMPLE, &TreeOptions::default(), &parameters).unwrap_err();
+
+        assert!(matches!(cd_above_root, Error::Day { line: Some(2), .. }));
+        assert_eq!(cd_above_root.day_error::<TreeError>(), Some(&TreeError::CdAboveRoot));
+        assert_eq!(unknown_command.day_error::<TreeError>(), Some(&TreeError::UnknownCommand { command: String::from("pwd") }));
+        assert_eq!(no_such_entry.day_error::<TreeError>(), Some(&TreeError::NoSuchEntry { path: String::from("/x") }));
+        assert!(matches!(not_enough_space, Error::Day { line: None, .. }));
+        assert!(matches!(not_enough_space.day_error::<TreeError>(), Some(TreeError::NotEnoughSpace { .. })))
+    }
 }
@@ -1,12 +1,157 @@
-use std::error::Error;
+use std::env;
+use std::io::{self, BufRead};
 use std::path::Path;
-use day_07_no_space_left::run;
+use std::process::ExitCode;
+use aoc_common::{open, Solution};
+use cli::{Command, FindOptions, Options, View};
+use day_07_no_space_left::{extension_totals, human_readable, run_reader_with, DiskUsage, LargestDirectories, ScanOptions, Timeline, Tree, TreeListing, TreeOptions, UnifiedDiff};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let filename = Path::new("../input.txt");
+mod cli;
 
-    let sizes = run(filename)?;
-    println!("Sizes: {:?}", sizes);
+fn main() -> ExitCode {
+    let command = match cli::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
 
-    Ok(())
+    match command {
+        Command::Solve(options) => match (&options.scan, &options.diff, &options.view) {
+            (Some(dir), _, _) => scan(dir, &options.scan_options),
+            (None, Some(later), _) => diff(&options, later),
+            (None, None, _) if options.timeline => timeline(&options),
+            (None, None, Some(view)) => render(&options, view),
+            (None, None, None) => solve(&options)
+        },
+        Command::Find(options) => find(&options),
+        Command::Help => {
+            print!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// Opens the transcript at `path`, or stdin if it's `-`, so a live terminal log can be piped in
+fn open_input(path: &Path) -> aoc_common::Result<Box<dyn BufRead>> {
+    if path == Path::new("-") {
+        Ok(Box::new(io::stdin().lock()))
+    } else {
+        Ok(Box::new(open(path)?))
+    }
+}
+
+fn load(path: &Path, options: &TreeOptions) -> aoc_common::Result<Tree> {
+    Tree::from_reader(open_input(path)?, options)
+}
+
+fn solve(options: &Options) -> ExitCode {
+    match open_input(&options.input).and_then(|reader| run_reader_with(reader, &options.tree_options, &options.parameters)) {
+        Ok(results) => {
+            for warning in results.warnings() {
+                eprintln!("warning: line {}: {warning}", warning.line);
+            }
+
+            println!("part 1: {}", results.part_1());
+            println!("part 2: {}", results.part_2());
+            ExitCode::SUCCESS
+        },
+        Err(err) => {
+            eprintln!("error: {}: {err}", options.input.display());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn render(options: &Options, view: &View) -> ExitCode {
+    let tree = match load(&options.input, &options.tree_options) {
+        Ok(tree) => tree,
+        Err(err) => {
+            eprintln!("error: {}: {err}", options.input.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match view {
+        View::Tree => print!("{}", TreeListing::new(tree.root(), options.render)),
+        View::DiskUsage => print!("{}", DiskUsage::new(tree.root(), options.render)),
+        View::Largest => print!("{}", LargestDirectories::new(tree.root(), options.count, options.render))
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn diff(options: &Options, later: &Path) -> ExitCode {
+    let load = |path: &Path| load(path, &options.tree_options).map_err(|err| eprintln!("error: {}: {err}", path.display()));
+    let (Ok(old), Ok(new)) = (load(&options.input), load(later)) else {
+        return ExitCode::FAILURE;
+    };
+
+    let diff = day_07_no_space_left::diff(&old, &new);
+    print!("{}", UnifiedDiff::new(&diff, &options.input.display().to_string(), &later.display().to_string()));
+    ExitCode::SUCCESS
+}
+
+fn timeline(options: &Options) -> ExitCode {
+    match open_input(&options.input).and_then(|reader| Timeline::from_reader(reader, &options.tree_options, &options.watched)) {
+        Ok(timeline) => {
+            print!("{timeline}");
+            ExitCode::SUCCESS
+        },
+        Err(err) => {
+            eprintln!("error: {}: {err}", options.input.display());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn find(options: &FindOptions) -> ExitCode {
+    let tree = match load(&options.input, &options.tree_options) {
+        Ok(tree) => tree,
+        Err(err) => {
+            eprintln!("error: {}: {err}", options.input.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let size = |size: u64| if options.human_readable {
+        human_readable(size)
+    } else {
+        size.to_string()
+    };
+
+    let files = day_07_no_space_left::find(tree.root(), |file| options.query.matches(file));
+    if options.by_extension {
+        for (extension, total) in extension_totals(files) {
+            let extension = match extension.as_str() {
+                "" => String::from("(none)"),
+                _ => format!(".{extension}")
+            };
+            println!("{}\t{}\t{extension}", size(total.size), total.files);
+        }
+    } else {
+        for file in files {
+            println!("{}\t{}", size(file.size()), file.path());
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn scan(dir: &Path, options: &ScanOptions) -> ExitCode {
+    match day_07_no_space_left::scan(dir, options) {
+        Ok(scan) => {
+            for path in &scan.skipped {
+                eprintln!("warning: skipped {}", path.display());
+            }
+
+            print!("{}", scan.transcript);
+            ExitCode::SUCCESS
+        },
+        Err(err) => {
+            eprintln!("error: {}: {err}", dir.display());
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,318 @@
+use crate::{NodeRef, Parameters, Tree, TreeError};
+
+/// Options for [`plan_deletions`]
+#[derive(Debug, Clone)]
+pub struct PlanOptions {
+    /// Whether single files may be deleted, as well as whole directories
+    pub include_files: bool,
+    /// Most candidates planned exactly, above which the plan is found greedily
+    pub exact_limit: usize
+}
+
+impl Default for PlanOptions {
+    fn default() -> Self {
+        PlanOptions { include_files: false, exact_limit: 20 }
+    }
+}
+
+/// Files and directories to delete to leave enough unused space for the update
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// Absolute paths to delete, none inside another, in listing order
+    pub paths: Vec<String>,
+    /// Total size of the deleted paths
+    pub freed: u64,
+    /// Whether the plan is the smallest possible, rather than a greedy estimate
+    pub exact: bool
+}
+
+/// Finds directories (and files, if `options` allows) to delete that leave `parameters.min_unused_space`
+/// unused, freeing as little space as possible
+///
+/// With at most `options.exact_limit` candidates every combination is considered, otherwise the
+/// plan is built greedily from the largest candidates, and is never worse than deleting the single
+/// smallest directory that's large enough.
+pub fn plan_deletions(tree: &Tree, parameters: &Parameters, options: &PlanOptions) -> Result<Plan, TreeError> {
+    let not_enough_space = || TreeError::NotEnoughSpace {
+        capacity: parameters.capacity,
+        min_unused_space: parameters.min_unused_space
+    };
+
+    let total_size = tree.total_size();
+    let required = total_size.checked_add(parameters.min_unused_space)
+        .map(|size| size.saturating_sub(parameters.capacity))
+        .filter(|required| *required <= total_size)
+        .ok_or_else(not_enough_space)?;
+
+    let candidates = candidates(tree, options.include_files);
+    let exact = candidates.len() <= options.exact_limit;
+    let chosen = if exact {
+        exact_plan(&candidates, required)
+    } else {
+        greedy_plan(&candidates, required)
+    };
+
+    let mut chosen = chosen.ok_or_else(not_enough_space)?;
+    chosen.sort_unstable();
+
+    Ok(Plan {
+        paths: chosen.iter().map(|i| candidates[*i].node.path()).collect(),
+        freed: chosen.iter().map(|i| candidates[*i].size).sum(),
+        exact
+    })
+}
+
+/// A file or directory that could be deleted
+struct Candidate<'a> {
+    node: NodeRef<'a>,
+    size: u64,
+    /// Index of the closest candidate holding this one
+    parent: Option<usize>,
+    /// Index after the last candidate inside this one
+    end: usize
+}
+
+/// Every non-empty directory, and file if `include_files` is set, in listing order
+fn candidates(tree: &Tree, include_files: bool) -> Vec<Candidate<'_>> {
+    let mut candidates: Vec<Candidate> = Vec::new();
+    // candidates still collecting the candidates inside them, with their depths
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut pending = vec![(NodeRef::Directory(tree.root()), 0)];
+
+    while let Some((node, depth)) = pending.pop() {
+        // deleting nothing never helps, and everything inside an empty directory is empty too
+        if node.size() == 0 || (!include_files && node.as_file().is_some()) {
+            continue;
+        }
+
+        while open.last().is_some_and(|(_, open_depth)| *open_depth >= depth) {
+            if let Some((i, _)) = open.pop() {
+                candidates[i].end = candidates.len();
+            }
+        }
+
+        let index = candidates.len();
+        candidates.push(Candidate { node, size: node.size(), parent: open.last().map(|(i, _)| *i), end: index + 1 });
+
+        if let Some(dir) = node.as_directory() {
+            open.push((index, depth));
+            let children = dir.children().collect::<Vec<_>>();
+            pending.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+    }
+
+    for (i, _) in open {
+        candidates[i].end = candidates.len();
+    }
+
+    candidates
+}
+
+/// The smallest total size of candidates, none inside another, that's at least `required`
+fn exact_plan(candidates: &[Candidate], required: u64) -> Option<Vec<usize>> {
+    // most that can be freed by the candidates from each index on, for giving up on hopeless branches
+    let mut reach = vec![0u64; candidates.len() + 1];
+    for i in (0..candidates.len()).rev() {
+        reach[i] = reach[i + 1].max(candidates[i].size.saturating_add(reach[candidates[i].end]));
+    }
+
+    let mut search = Search { candidates, reach, required, chosen: Vec::new(), best: None };
+    search.search(0, 0);
+    search.best.map(|(_, chosen)| chosen)
+}
+
+struct Search<'c, 'a> {
+    candidates: &'c [Candidate<'a>],
+    reach: Vec<u64>,
+    required: u64,
+    chosen: Vec<usize>,
+    best: Option<(u64, Vec<usize>)>
+}
+
+impl Search<'_, '_> {
+    /// Tries deleting and keeping candidate `i`, having freed `freed` with the candidates before it
+    fn search(&mut self, i: usize, freed: u64) {
+        if freed >= self.required {
+            if self.best.as_ref().is_none_or(|(best, _)| freed < *best) {
+                self.best = Some((freed, self.chosen.clone()));
+            }
+            return;
+        }
+
+        if i == self.candidates.len() || freed.saturating_add(self.reach[i]) < self.required {
+            return;
+        }
+
+        let candidate = &self.candidates[i];
+        self.chosen.push(i);
+        self.search(candidate.end, freed.saturating_add(candidate.size));
+        self.chosen.pop();
+
+        self.search(i + 1, freed);
+    }
+}
+
+/// Takes the largest candidates that don't overshoot `required`, then the smallest that makes up the rest,
+/// falling back to the smallest single candidate if that's better
+fn greedy_plan(candidates: &[Candidate], required: u64) -> Option<Vec<usize>> {
+    let mut order = (0..candidates.len()).collect::<Vec<_>>();
+    order.sort_by_key(|i| std::cmp::Reverse(candidates[*i].size));
+
+    // candidates inside or holding a chosen candidate
+    let mut blocked = vec![false; candidates.len()];
+    let mut chosen = Vec::new();
+    let mut freed = 0;
+
+    let mut choose = |i: usize, blocked: &mut [bool]| {
+        chosen.push(i);
+        blocked[i..candidates[i].end].fill(true);
+        let mut parent = candidates[i].parent;
+        while let Some(p) = parent.filter(|p| !blocked[*p]) {
+            blocked[p] = true;
+            parent = candidates[p].parent;
+        }
+        candidates[i].size
+    };
+
+    for i in &order {
+        if !blocked[*i] && freed + candidates[*i].size <= required {
+            freed += choose(*i, &mut blocked);
+        }
+    }
+
+    if freed < required {
+        let remaining = required - freed;
+        if let Some(i) = order.iter().rev().find(|i| !blocked[**i] && candidates[**i].size >= remaining) {
+            freed += choose(*i, &mut blocked);
+        }
+    }
+
+    let greedy = Some((freed, chosen)).filter(|(freed, _)| *freed >= required);
+    let single = order.iter().rev()
+        .find(|i| candidates[**i].size >= required)
+        .map(|i| (candidates[*i].size, vec![*i]));
+
+    match (greedy, single) {
+        (Some(greedy), Some(single)) if single.0 < greedy.0 => Some(single.1),
+        (Some(greedy), _) => Some(greedy.1),
+        (None, single) => single.map(|(_, chosen)| chosen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_common::Solution;
+    use proptest::prelude::*;
+    use crate::tests::EXAMPLE;
+    use crate::{generate, run_lines_with, GeneratorOptions, NodeRef, Parameters, Tree, TreeError, TreeOptions};
+    use super::{plan_deletions, Plan, PlanOptions};
+
+    /// `/` holding `/a` (`/a/b` and a file), `/c` and a file, 220 bytes in all
+    const TRANSCRIPT: &str = "$ cd /\n$ ls\ndir a\ndir c\n10 x\n$ cd a\n$ ls\ndir b\n40 y\n$ cd b\n$ ls\n60 z\n$ cd /c\n$ ls\n110 w\n";
+
+    fn plan(transcript: &str, required: u64, options: &PlanOptions) -> Result<Plan, TreeError> {
+        let tree = Tree::from_input(transcript, &TreeOptions::default()).unwrap();
+        let parameters = Parameters { capacity: tree.total_size(), min_unused_space: required, ..Parameters::default() };
+        plan_deletions(&tree, &parameters, options)
+    }
+
+    #[test]
+    fn example_directories() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let plan = plan_deletions(&tree, &Parameters::default(), &PlanOptions::default()).unwrap();
+
+        assert_eq!(plan, Plan { paths: vec![String::from("/d")], freed: 24_933_642, exact: true })
+    }
+
+    #[test]
+    fn example_files() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let options = PlanOptions { include_files: true, ..PlanOptions::default() };
+        let plan = plan_deletions(&tree, &Parameters::default(), &options).unwrap();
+
+        assert_eq!(plan, Plan { paths: vec![String::from("/c.dat")], freed: 8_504_156, exact: true })
+    }
+
+    #[test]
+    fn several_directories() {
+        // /a/b and /c beat the smallest single directory large enough, /
+        let plan = plan(TRANSCRIPT, 170, &PlanOptions::default()).unwrap();
+
+        assert_eq!((plan.paths, plan.freed), (vec![String::from("/a/b"), String::from("/c")], 170))
+    }
+
+    #[test]
+    fn nested_directories_not_counted_twice() {
+        // /a and /a/b would add up to exactly 160, but only free 100
+        let plan = plan(TRANSCRIPT, 160, &PlanOptions::default()).unwrap();
+
+        assert_eq!((plan.paths, plan.freed), (vec![String::from("/a/b"), String::from("/c")], 170))
+    }
+
+    #[test]
+    fn greedy() {
+        let options = PlanOptions { exact_limit: 0, ..PlanOptions::default() };
+        let plan = plan(TRANSCRIPT, 170, &options).unwrap();
+
+        assert_eq!((plan.paths, plan.freed, plan.exact), (vec![String::from("/a/b"), String::from("/c")], 170, false))
+    }
+
+    #[test]
+    fn nothing_to_delete() {
+        let plan = plan(TRANSCRIPT, 0, &PlanOptions::default()).unwrap();
+
+        assert_eq!((plan.paths.len(), plan.freed), (0, 0))
+    }
+
+    #[test]
+    fn not_enough_space() {
+        let err = plan(TRANSCRIPT, 221, &PlanOptions::default()).unwrap_err();
+
+        assert_eq!(err, TreeError::NotEnoughSpace { capacity: 220, min_unused_space: 221 })
+    }
+
+    /// The smallest total of directories, none inside another, that's at least `required`, trying every subset
+    fn brute_force(tree: &Tree, required: u64) -> Option<u64> {
+        let dirs = tree.root().walk().filter_map(NodeRef::as_directory).map(|dir| dir.path()).collect::<Vec<_>>();
+        let nested = |a: &str, b: &str| a == "/" || b.starts_with(&format!("{a}/"));
+
+        (0u32..1 << dirs.len())
+            .map(|set| (0..dirs.len()).filter(|i| set & (1 << i) != 0).collect::<Vec<_>>())
+            .filter(|set| set.iter().all(|a| set.iter().all(|b| a == b || !nested(&dirs[*a], &dirs[*b]))))
+            .map(|set| set.iter().map(|i| tree.resolve(&dirs[*i]).map_or(0, |node| node.size())).sum::<u64>())
+            .filter(|freed| *freed >= required)
+            .min()
+    }
+
+    proptest! {
+        #[test]
+        fn exact_plan_is_smallest(seed in any::<u64>(), directories in 0usize..10, required in 0u64..1_000) {
+            let generated = generate(seed, &GeneratorOptions { directories, files: 15, max_file_size: 100, revisits: 0 });
+            let tree = Tree::from_input(&generated.transcript, &TreeOptions::default()).unwrap();
+            let parameters = Parameters { capacity: tree.total_size(), min_unused_space: required, ..Parameters::default() };
+
+            let plan = plan_deletions(&tree, &parameters, &PlanOptions::default()).ok();
+            prop_assert_eq!(plan.map(|plan| plan.freed), brute_force(&tree, required));
+        }
+
+        #[test]
+        fn greedy_plan_beats_single_directory(seed in any::<u64>(), include_files in any::<bool>()) {
+            let generated = generate(seed, &GeneratorOptions { max_file_size: 1_000_000, ..GeneratorOptions::default() });
+            let tree = Tree::from_input(&generated.transcript, &TreeOptions::default()).unwrap();
+            let parameters = Parameters { capacity: tree.total_size(), min_unused_space: tree.total_size() / 3, ..Parameters::default() };
+
+            let options = PlanOptions { include_files, exact_limit: 0 };
+            let plan = plan_deletions(&tree, &parameters, &options).unwrap();
+            let size_2 = run_lines_with(&generated.transcript, &TreeOptions::default(), &parameters).unwrap().part_2();
+            prop_assert!(plan.freed >= parameters.min_unused_space && plan.freed <= size_2);
+
+            // no path is inside another, and the sizes add up
+            for (a, b) in plan.paths.iter().zip(plan.paths.iter().skip(1)) {
+                let inside = b.starts_with(&format!("{a}/"));
+                prop_assert!(!inside, "{} is inside {}", b, a);
+            }
+            let freed = plan.paths.iter().filter_map(|path| tree.resolve(path)).map(|node| node.size()).sum::<u64>();
+            prop_assert_eq!(freed, plan.freed);
+        }
+    }
+}
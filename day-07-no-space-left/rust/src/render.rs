@@ -0,0 +1,308 @@
+use std::fmt;
+use crate::{DirectoryRef, NodeRef};
+
+/// Options shared by the renderers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    /// Deepest level shown, where the rendered directory is at depth 0
+    pub max_depth: Option<usize>,
+    /// Print sizes like `du -h` does, e.g. `8.1M`, instead of in bytes
+    pub human_readable: bool
+}
+
+impl RenderOptions {
+    fn shows(&self, depth: usize) -> bool {
+        self.max_depth.is_none_or(|max_depth| depth <= max_depth)
+    }
+
+    fn size(&self, size: u64) -> String {
+        if self.human_readable {
+            human_readable(size)
+        } else {
+            size.to_string()
+        }
+    }
+}
+
+/// Formats `size` in powers of 1024 with a one-letter suffix, e.g. `584`, `8.2M` or `47M`
+///
+/// Like `du -h`, sizes are rounded up, so they're never shown smaller than they are, and a size that
+/// rounds up to 1024 of one unit is shown as 1.0 of the next.
+pub fn human_readable(size: u64) -> String {
+    const SUFFIXES: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+
+    if size < 1024 {
+        return size.to_string();
+    }
+
+    let mut value = size as f64;
+    for (i, suffix) in SUFFIXES.iter().enumerate() {
+        value /= 1024.0;
+        let rounded = if value < 10.0 { (value * 10.0).ceil() / 10.0 } else { value.ceil() };
+        if rounded < 1024.0 || i == SUFFIXES.len() - 1 {
+            return if rounded < 10.0 { format!("{rounded:.1}{suffix}") } else { format!("{rounded:.0}{suffix}") };
+        }
+    }
+
+    unreachable!("the last suffix is always used")
+}
+
+/// Indented listing in the puzzle's notation, e.g. `- i (file, size=584)`
+pub struct TreeListing<'a> {
+    dir: DirectoryRef<'a>,
+    options: RenderOptions
+}
+
+impl<'a> TreeListing<'a> {
+    pub fn new(dir: DirectoryRef<'a>, options: RenderOptions) -> TreeListing<'a> {
+        TreeListing { dir, options }
+    }
+
+}
+
+impl fmt::Display for TreeListing<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // nodes still to be written with their depths, the next one last
+        let mut pending = vec![(NodeRef::Directory(self.dir), 0)];
+        while let Some((node, depth)) = pending.pop() {
+            let indent = "  ".repeat(depth);
+            match node {
+                NodeRef::Directory(dir) => {
+                    writeln!(f, "{indent}- {} (dir)", dir.name())?;
+                    if self.options.shows(depth + 1) {
+                        let children = dir.children().collect::<Vec<_>>();
+                        pending.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+                    }
+                },
+
+                NodeRef::File(file) => writeln!(f, "{indent}- {} (file, size={})", file.name(), self.options.size(file.size()))?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Size and path of every file and directory, directories after their contents, like `du -a`
+pub struct DiskUsage<'a> {
+    dir: DirectoryRef<'a>,
+    options: RenderOptions
+}
+
+impl<'a> DiskUsage<'a> {
+    pub fn new(dir: DirectoryRef<'a>, options: RenderOptions) -> DiskUsage<'a> {
+        DiskUsage { dir, options }
+    }
+
+}
+
+impl fmt::Display for DiskUsage<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // nodes still to be written with their depths, where a directory is pushed again once its
+        // contents have been pushed, so it's written after them
+        let mut pending = vec![(NodeRef::Directory(self.dir), 0, false)];
+        while let Some((node, depth, expanded)) = pending.pop() {
+            if let NodeRef::Directory(dir) = node {
+                if !expanded && self.options.shows(depth + 1) {
+                    pending.push((node, depth, true));
+                    let children = dir.children().collect::<Vec<_>>();
+                    pending.extend(children.into_iter().rev().map(|child| (child, depth + 1, false)));
+                    continue;
+                }
+            }
+
+            writeln!(f, "{}\t{}", self.options.size(node.size()), node.path())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Table of the `count` largest directories, largest first
+pub struct LargestDirectories<'a> {
+    dir: DirectoryRef<'a>,
+    count: usize,
+    options: RenderOptions
+}
+
+impl<'a> LargestDirectories<'a> {
+    pub fn new(dir: DirectoryRef<'a>, count: usize, options: RenderOptions) -> LargestDirectories<'a> {
+        LargestDirectories { dir, count, options }
+    }
+
+    fn directories(&self) -> Vec<DirectoryRef<'a>> {
+        let mut directories = Vec::new();
+        let mut pending = vec![(self.dir, 0)];
+        while let Some((dir, depth)) = pending.pop() {
+            directories.push(dir);
+            if self.options.shows(depth + 1) {
+                pending.extend(dir.children().filter_map(NodeRef::as_directory).map(|child| (child, depth + 1)));
+            }
+        }
+
+        directories
+    }
+}
+
+impl fmt::Display for LargestDirectories<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows = self.directories().iter().map(|dir| (dir.size(), dir.path())).collect::<Vec<_>>();
+        rows.sort_by(|(size_a, path_a), (size_b, path_b)| size_b.cmp(size_a).then_with(|| path_a.cmp(path_b)));
+        rows.truncate(self.count);
+
+        let sizes = rows.iter().map(|(size, _)| self.options.size(*size)).collect::<Vec<_>>();
+        let width = sizes.iter().map(String::len).max().unwrap_or(0).max("SIZE".len());
+
+        writeln!(f, "{:>width$}  PATH", "SIZE")?;
+        for (size, (_, path)) in sizes.iter().zip(rows.iter()) {
+            writeln!(f, "{size:>width$}  {path}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use crate::tests::EXAMPLE;
+    use crate::{Tree, TreeOptions};
+    use super::{human_readable, DiskUsage, LargestDirectories, RenderOptions, TreeListing};
+
+    fn tree() -> Tree {
+        Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn tree_listing() {
+        let tree = tree();
+
+        assert_eq!(TreeListing::new(tree.root(), RenderOptions::default()).to_string(), "\
+- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - j (file, size=4060174)
+    - d.log (file, size=8033020)
+    - d.ext (file, size=5626152)
+    - k (file, size=7214296)
+")
+    }
+
+    #[test]
+    fn tree_listing_max_depth() {
+        let tree = tree();
+        let options = RenderOptions { max_depth: Some(1), human_readable: true };
+
+        assert_eq!(TreeListing::new(tree.root(), options).to_string(), "\
+- / (dir)
+  - a (dir)
+  - b.txt (file, size=15M)
+  - c.dat (file, size=8.2M)
+  - d (dir)
+")
+    }
+
+    #[test]
+    fn disk_usage() {
+        let tree = tree();
+        let a = tree.root().directory("a").unwrap();
+
+        assert_eq!(DiskUsage::new(a, RenderOptions::default()).to_string(), "\
+584\t/a/e/i
+584\t/a/e
+29116\t/a/f
+2557\t/a/g
+62596\t/a/h.lst
+94853\t/a
+")
+    }
+
+    #[test]
+    fn disk_usage_max_depth() {
+        let tree = tree();
+        let options = RenderOptions { max_depth: Some(1), human_readable: true };
+
+        assert_eq!(DiskUsage::new(tree.root(), options).to_string(), "\
+93K\t/a
+15M\t/b.txt
+8.2M\t/c.dat
+24M\t/d
+47M\t/
+")
+    }
+
+    #[test]
+    fn largest_directories() {
+        let tree = tree();
+
+        assert_eq!(LargestDirectories::new(tree.root(), 3, RenderOptions::default()).to_string(), "    SIZE  PATH
+48381165  /
+24933642  /d
+   94853  /a
+")
+    }
+
+    #[test]
+    fn largest_directories_max_depth() {
+        let tree = tree();
+        let options = RenderOptions { max_depth: Some(1), human_readable: true };
+
+        assert_eq!(LargestDirectories::new(tree.root(), 10, options).to_string(), "\
+SIZE  PATH
+ 47M  /
+ 24M  /d
+ 93K  /a
+")
+    }
+
+    /// Tree of `/` holding a chain of `depth` directories named `d`, with a file `f` of size 1 at the bottom
+    fn deep_tree(depth: usize) -> Tree {
+        let transcript = format!("{}$ ls\n1 f\n", "$ ls\ndir d\n$ cd d\n".repeat(depth));
+        Tree::from_input(&transcript, &TreeOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn deep_tree_renders() {
+        // a stack this small overflows if the renderers recurse once per level
+        let rendered = thread::Builder::new().stack_size(128 * 1024).spawn(|| {
+            let tree = deep_tree(2_000);
+            let options = RenderOptions::default();
+            [
+                TreeListing::new(tree.root(), options).to_string(),
+                DiskUsage::new(tree.root(), options).to_string(),
+                LargestDirectories::new(tree.root(), 1, options).to_string()
+            ]
+        }).unwrap().join().unwrap();
+
+        assert_eq!(rendered[0].lines().count(), 2_002);
+        assert!(rendered[0].ends_with(&format!("{}- f (file, size=1)\n", "  ".repeat(2_001))));
+        assert_eq!(rendered[1].lines().count(), 2_002);
+        assert!(rendered[1].starts_with(&format!("1\t{}/f\n", "/d".repeat(2_000))));
+        assert_eq!(rendered[2], "SIZE  PATH\n   1  /\n")
+    }
+
+    #[test]
+    fn deep_tree_stops_at_max_depth() {
+        let tree = deep_tree(100_000);
+        let options = RenderOptions { max_depth: Some(1), human_readable: false };
+
+        assert_eq!(TreeListing::new(tree.root(), options).to_string(), "- / (dir)\n  - d (dir)\n");
+        assert_eq!(DiskUsage::new(tree.root(), options).to_string(), "1\t/d\n1\t/\n");
+        assert_eq!(LargestDirectories::new(tree.root(), 5, options).to_string(), "SIZE  PATH\n   1  /\n   1  /d\n")
+    }
+
+    #[test]
+    fn human_readable_units() {
+        let sizes = [0, 1023, 1024, 1536, 1537, 10_188, 10_240, 10_241, 1_048_575, 3 * 1024 * 1024 * 1024 * 1024, u64::MAX]
+            .map(human_readable);
+
+        assert_eq!(sizes, ["0", "1023", "1.0K", "1.5K", "1.6K", "10K", "10K", "11K", "1.0M", "3.0T", "16E"])
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Options for writing a transcript of a directory on disk
+#[derive(Debug, Clone, Default)]
+pub struct ScanOptions {
+    pub traversal: Traversal,
+    pub symlinks: SymlinkPolicy,
+    /// Deepest level listed, where the scanned directory is at depth 0
+    pub max_depth: Option<usize>
+}
+
+/// Order the directories are visited in, each listing its entries sorted by name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Traversal {
+    /// Enter each subdirectory as soon as its parent is listed, like the puzzle's transcript
+    #[default]
+    DepthFirst,
+    /// List every directory at one depth before going deeper
+    BreadthFirst
+}
+
+/// How symbolic links are listed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Leave links out of the transcript
+    #[default]
+    Skip,
+    /// List what the link points to, skipping links to one of their own ancestors
+    Follow,
+    /// List the link itself as a file
+    AsFile
+}
+
+/// A transcript written by [`scan`]
+#[derive(Debug)]
+pub struct Scan {
+    pub transcript: String,
+    /// Entries left out of the transcript, e.g. skipped links or names that don't fit on a line
+    pub skipped: Vec<PathBuf>
+}
+
+/// Walks the directory at `path` and writes the `$ cd`/`$ ls` transcript that would list it
+pub fn scan<P>(path: P, options: &ScanOptions) -> io::Result<Scan>
+where P: AsRef<Path> {
+    let mut writer = Writer { transcript: String::from("$ cd /\n"), current: Vec::new(), skipped: Vec::new() };
+    let root = Pending { path: path.as_ref().to_path_buf(), names: Vec::new(), ancestors: Vec::new() };
+    let mut pending = VecDeque::from([root]);
+
+    while let Some(dir) = pending.pop_front() {
+        let children = writer.list(&dir, options)?;
+        match options.traversal {
+            Traversal::DepthFirst => children.into_iter().rev().for_each(|child| pending.push_front(child)),
+            Traversal::BreadthFirst => pending.extend(children)
+        }
+    }
+
+    Ok(Scan { transcript: writer.transcript, skipped: writer.skipped })
+}
+
+/// A directory waiting to be listed
+struct Pending {
+    path: PathBuf,
+    /// Names from `/` down to the directory
+    names: Vec<String>,
+    /// Canonical paths of the directory and its ancestors, for spotting link cycles
+    ancestors: Vec<PathBuf>
+}
+
+struct Writer {
+    transcript: String,
+    current: Vec<String>,
+    skipped: Vec<PathBuf>
+}
+
+impl Writer {
+    /// Lists `dir`, returning the subdirectories to list after it
+    fn list(&mut self, dir: &Pending, options: &ScanOptions) -> io::Result<Vec<Pending>> {
+        self.cd(&dir.names);
+        self.transcript.push_str("$ ls\n");
+
+        let mut ancestors = dir.ancestors.clone();
+        ancestors.push(fs::canonicalize(&dir.path)?);
+
+        let mut entries = fs::read_dir(&dir.path)?.collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let enter = options.max_depth.is_none_or(|max_depth| dir.names.len() < max_depth);
+        let mut children = Vec::new();
+        for entry in entries {
+            let path = entry.path();
+            let name = match entry.file_name().into_string() {
+                Ok(name) if !name.contains(['\n', '\r']) => name,
+                _ => {
+                    self.skipped.push(path);
+                    continue;
+                }
+            };
+
+            let mut metadata = fs::symlink_metadata(&path)?;
+            if metadata.file_type().is_symlink() {
+                match options.symlinks {
+                    SymlinkPolicy::Skip => {
+                        self.skipped.push(path);
+                        continue;
+                    },
+                    SymlinkPolicy::Follow => match fs::metadata(&path) {
+                        Ok(target) if target.is_dir() && ancestors.contains(&fs::canonicalize(&path)?) => {
+                            self.skipped.push(path);
+                            continue;
+                        },
+                        Ok(target) => metadata = target,
+                        // dangling link
+                        Err(_) => {
+                            self.skipped.push(path);
+                            continue;
+                        }
+                    },
+                    SymlinkPolicy::AsFile => ()
+                }
+            }
+
+            if metadata.is_dir() {
+                self.transcript.push_str(&format!("dir {name}\n"));
+                if enter {
+                    let mut names = dir.names.clone();
+                    names.push(name);
+                    children.push(Pending { path, names, ancestors: ancestors.clone() });
+                }
+            } else {
+                self.transcript.push_str(&format!("{} {name}\n", metadata.len()));
+            }
+        }
+
+        Ok(children)
+    }
+
+    /// Moves from the current directory to the directory at `names`
+    fn cd(&mut self, names: &[String]) {
+        let common = self.current.iter().zip(names).take_while(|(current, name)| current == name).count();
+        if common == 0 && self.current.len() > 1 {
+            self.transcript.push_str("$ cd /\n");
+        } else {
+            for _ in common..self.current.len() {
+                self.transcript.push_str("$ cd ..\n");
+            }
+        }
+
+        self.current.truncate(common);
+        for name in &names[common..] {
+            self.transcript.push_str(&format!("$ cd {name}\n"));
+            self.current.push(name.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use crate::{NodeRef, Tree, TreeOptions};
+    use super::{scan, ScanOptions, SymlinkPolicy, Traversal};
+
+    /// Builds `/a/e/i`, `/a/f`, `/b.txt`, `/d/j` and `/d/my notes.txt`
+    fn example_dir() -> tempfile::TempDir {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("a/e")).unwrap();
+        fs::create_dir(root.path().join("d")).unwrap();
+        fs::write(root.path().join("a/e/i"), vec![0; 584]).unwrap();
+        fs::write(root.path().join("a/f"), vec![0; 2_911]).unwrap();
+        fs::write(root.path().join("b.txt"), vec![0; 14_848]).unwrap();
+        fs::write(root.path().join("d/j"), vec![0; 4_060]).unwrap();
+        fs::write(root.path().join("d/my notes.txt"), vec![0; 80]).unwrap();
+        root
+    }
+
+    /// Total size of the files in `path` found by walking it directly
+    fn du(path: &Path) -> u64 {
+        fs::read_dir(path).unwrap().map(|entry| {
+            let entry = entry.unwrap();
+            let metadata = fs::symlink_metadata(entry.path()).unwrap();
+            if metadata.is_dir() {
+                du(&entry.path())
+            } else if metadata.file_type().is_symlink() {
+                0
+            } else {
+                metadata.len()
+            }
+        }).sum()
+    }
+
+    #[test]
+    fn depth_first_transcript() {
+        let root = example_dir();
+        let scan = scan(root.path(), &ScanOptions::default()).unwrap();
+
+        assert_eq!(scan.transcript, "\
+$ cd /
+$ ls
+dir a
+14848 b.txt
+dir d
+$ cd a
+$ ls
+dir e
+2911 f
+$ cd e
+$ ls
+584 i
+$ cd /
+$ cd d
+$ ls
+4060 j
+80 my notes.txt
+")
+    }
+
+    #[test]
+    fn breadth_first_transcript() {
+        let root = example_dir();
+        let options = ScanOptions { traversal: Traversal::BreadthFirst, ..ScanOptions::default() };
+        let scan = scan(root.path(), &options).unwrap();
+        let commands = scan.transcript.lines().filter(|line| line.starts_with("$ cd")).collect::<Vec<_>>();
+
+        assert_eq!(commands, vec!["$ cd /", "$ cd a", "$ cd ..", "$ cd d", "$ cd ..", "$ cd a", "$ cd e"])
+    }
+
+    #[test]
+    fn transcript_totals_match_direct_walk() {
+        let root = example_dir();
+        for traversal in [Traversal::DepthFirst, Traversal::BreadthFirst] {
+            let scan = scan(root.path(), &ScanOptions { traversal, ..ScanOptions::default() }).unwrap();
+            let tree = Tree::from_input(&scan.transcript, &TreeOptions::default()).unwrap();
+
+            for dir in tree.root().walk().filter_map(NodeRef::as_directory) {
+                let path = root.path().join(dir.path().trim_start_matches('/'));
+                assert_eq!(dir.size(), du(&path), "{}", dir.path());
+            }
+        }
+    }
+
+    #[test]
+    fn max_depth() {
+        let root = example_dir();
+        let options = ScanOptions { max_depth: Some(1), ..ScanOptions::default() };
+        let scan = scan(root.path(), &options).unwrap();
+        let tree = Tree::from_input(&scan.transcript, &TreeOptions::default()).unwrap();
+
+        // a is listed but e isn't entered
+        assert_eq!(tree.resolve("/a").map(|node| node.size()), Some(2_911));
+        assert!(tree.resolve("/a/e").is_some_and(|node| node.size() == 0))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks() {
+        let root = example_dir();
+        std::os::unix::fs::symlink(root.path().join("d"), root.path().join("link")).unwrap();
+        std::os::unix::fs::symlink(root.path(), root.path().join("a/up")).unwrap();
+        let totals = [SymlinkPolicy::Skip, SymlinkPolicy::Follow, SymlinkPolicy::AsFile].map(|symlinks| {
+            let scan = scan(root.path(), &ScanOptions { symlinks, ..ScanOptions::default() }).unwrap();
+            let tree = Tree::from_input(&scan.transcript, &TreeOptions::default()).unwrap();
+            (tree.resolve("/link").map(|node| node.size()), scan.skipped.len())
+        });
+
+        // following skips the link back up to /, since it would never end
+        let link_size = fs::symlink_metadata(root.path().join("link")).unwrap().len();
+        assert_eq!(totals, [(None, 2), (Some(4_140), 1), (Some(link_size), 0)])
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt;
+use std::io::BufRead;
+use std::path::Path;
+use aoc_common::{open, TryLine};
+use crate::{Tree, TreeOptions};
+
+/// Sizes after each command in a transcript, kept up to date with [`Tree::push_line`]
+/// rather than calculated once at the end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    /// Absolute paths whose sizes are recorded in every snapshot
+    pub watched: Vec<String>,
+    pub snapshots: Vec<Snapshot>,
+    /// Total size after each line, so line `n` is at index `n - 1`
+    totals: Vec<u64>
+}
+
+/// Sizes once a command and its output have been handled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Last line of the command's output, or the command itself when it has none
+    pub line: usize,
+    /// The command without its `$ `, or empty for output listed before any command
+    pub command: String,
+    pub total_size: u64,
+    /// Size of each watched path, or `None` while it doesn't exist
+    pub sizes: Vec<Option<u64>>
+}
+
+impl Timeline {
+    /// Replays the transcript `input`, recording the sizes of the `watched` paths after each command
+    pub fn from_input<S>(input: &str, options: &TreeOptions, watched: &[S]) -> aoc_common::Result<Timeline>
+    where S: AsRef<str> {
+        Timeline::from_lines(input.lines(), options, watched)
+    }
+
+    /// Replays the transcript at file path `filename`, recording the sizes of the `watched` paths after each command
+    pub fn from_file<P, S>(filename: P, options: &TreeOptions, watched: &[S]) -> aoc_common::Result<Timeline>
+    where P: AsRef<Path>, S: AsRef<str> {
+        Timeline::from_reader(open(filename)?, options, watched)
+    }
+
+    /// Replays a transcript read from `reader`, recording the sizes of the `watched` paths after each command
+    pub fn from_reader<R, S>(reader: R, options: &TreeOptions, watched: &[S]) -> aoc_common::Result<Timeline>
+    where R: BufRead, S: AsRef<str> {
+        Timeline::from_lines(reader.lines(), options, watched)
+    }
+
+    fn from_lines<I, S>(lines: I, options: &TreeOptions, watched: &[S]) -> aoc_common::Result<Timeline>
+    where I: IntoIterator, I::Item: TryLine, aoc_common::Error: From<<I::Item as TryLine>::Error>, S: AsRef<str> {
+        let mut timeline = Timeline { watched: watched.iter().map(|path| path.as_ref().to_string()).collect(), snapshots: Vec::new(), totals: Vec::new() };
+        let mut tree = Tree::incremental(options);
+        let mut command = None;
+
+        for l in lines {
+            let line = l.try_line()?;
+            let line = line.as_ref();
+            let line = line.strip_suffix('\r').unwrap_or(line);
+
+            // a command ends when the next one starts
+            if let Some(next) = line.strip_prefix("$ ") {
+                if let Some(command) = command.replace(next.to_string()) {
+                    timeline.snapshot(&tree, command);
+                }
+            } else if command.is_none() {
+                command = Some(String::new());
+            }
+
+            tree.push_line(line)?;
+            timeline.totals.push(tree.total_size());
+        }
+
+        if let Some(command) = command {
+            timeline.snapshot(&tree, command);
+        }
+
+        Ok(timeline)
+    }
+
+    /// Total size once line `line` has been handled, counting every file listed by then
+    pub fn total_after(&self, line: usize) -> u64 {
+        match line.min(self.totals.len()) {
+            0 => 0,
+            line => self.totals[line - 1]
+        }
+    }
+
+    /// The first snapshot where the watched `path` is larger than `size`
+    pub fn first_exceeding(&self, path: &str, size: u64) -> Option<&Snapshot> {
+        let i = self.watched.iter().position(|watched| watched == path)?;
+        self.snapshots.iter().find(|snapshot| snapshot.sizes[i].is_some_and(|watched_size| watched_size > size))
+    }
+
+    fn snapshot(&mut self, tree: &Tree, command: String) {
+        let sizes = self.watched.iter().map(|path| tree.resolve(path).map(|node| node.size())).collect();
+        self.snapshots.push(Snapshot { line: tree.line_number(), command, total_size: tree.total_size(), sizes });
+    }
+}
+
+/// Writes the timeline as CSV, with a column for each watched path
+impl fmt::Display for Timeline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line,command,total")?;
+        for path in &self.watched {
+            write!(f, ",{}", csv_field(path))?;
+        }
+        writeln!(f)?;
+
+        for snapshot in &self.snapshots {
+            write!(f, "{},{},{}", snapshot.line, csv_field(&snapshot.command), snapshot.total_size)?;
+            for size in &snapshot.sizes {
+                match size {
+                    Some(size) => write!(f, ",{size}")?,
+                    None => write!(f, ",")?
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Quotes `field` if it holds a comma, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use crate::tests::EXAMPLE;
+    use crate::{generate, GeneratorOptions, Tree, TreeOptions};
+    use super::Timeline;
+
+    #[test]
+    fn example_snapshots() {
+        let timeline = Timeline::from_input(EXAMPLE, &TreeOptions::default(), &["/a", "/d"]).unwrap();
+        let snapshots = timeline.snapshots.iter()
+            .map(|snapshot| (snapshot.line, snapshot.command.as_str(), snapshot.total_size, snapshot.sizes.clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(snapshots, vec![
+            (1, "cd /", 0, vec![None, None]),
+            (6, "ls", 23_352_670, vec![Some(0), Some(0)]),
+            (7, "cd a", 23_352_670, vec![Some(0), Some(0)]),
+            (12, "ls", 23_446_939, vec![Some(94_269), Some(0)]),
+            (13, "cd e", 23_446_939, vec![Some(94_269), Some(0)]),
+            (15, "ls", 23_447_523, vec![Some(94_853), Some(0)]),
+            (16, "cd ..", 23_447_523, vec![Some(94_853), Some(0)]),
+            (17, "cd ..", 23_447_523, vec![Some(94_853), Some(0)]),
+            (18, "cd d", 23_447_523, vec![Some(94_853), Some(0)]),
+            (23, "ls", 48_381_165, vec![Some(94_853), Some(24_933_642)])
+        ])
+    }
+
+    #[test]
+    fn snapshots_match_rebuilt_prefixes() {
+        let transcript = format!("{EXAMPLE}$ touch 100 /a/e/i\n$ cd /\n$ rm -r /d\n$ mkdir /d\n$ cd /d\n$ ls\n5 x\n$ rm /b.txt\n");
+        let timeline = Timeline::from_input(&transcript, &TreeOptions::default(), &["/d"]).unwrap();
+        let lines = transcript.lines().collect::<Vec<_>>();
+
+        for snapshot in &timeline.snapshots {
+            let tree = Tree::from_input(&lines[..snapshot.line].join("\n"), &TreeOptions::default()).unwrap();
+            assert_eq!(snapshot.total_size, tree.total_size(), "line {}", snapshot.line);
+            assert_eq!(snapshot.sizes[0], tree.resolve("/d").map(|node| node.size()), "line {}", snapshot.line);
+        }
+    }
+
+    #[test]
+    fn total_after() {
+        let timeline = Timeline::from_input(EXAMPLE, &TreeOptions::default(), &[] as &[&str]).unwrap();
+
+        assert_eq!(timeline.total_after(0), 0);
+        assert_eq!(timeline.total_after(6), 23_352_670);
+        // line 20 is in the middle of the last listing, which has only listed j by then
+        assert_eq!(timeline.total_after(20), 23_447_523 + 4_060_174);
+        assert_eq!(timeline.total_after(100), 48_381_165)
+    }
+
+    #[test]
+    fn first_exceeding() {
+        let timeline = Timeline::from_input(EXAMPLE, &TreeOptions::default(), &["/a"]).unwrap();
+
+        assert_eq!(timeline.first_exceeding("/a", 94_300).map(|snapshot| snapshot.line), Some(15));
+        assert!(timeline.first_exceeding("/a", 100_000).is_none());
+        assert!(timeline.first_exceeding("/b", 0).is_none())
+    }
+
+    #[test]
+    fn csv() {
+        let transcript = "10 early\n$ cd /\n$ ls\ndir a,b\n5 x\n$ cd a,b\n$ ls\n7 y\n";
+        let timeline = Timeline::from_input(transcript, &TreeOptions::default(), &["/a,b"]).unwrap();
+
+        assert_eq!(timeline.to_string(), "\
+line,command,total,\"/a,b\"
+1,,10,
+2,cd /,10,
+5,ls,15,0
+6,\"cd a,b\",15,0
+8,ls,22,7
+")
+    }
+
+    #[test]
+    fn errors_keep_line_numbers() {
+        let err = Timeline::from_input("$ cd /\n$ ls\n5 x\n$ rm y\n", &TreeOptions::default(), &[] as &[&str]).unwrap_err();
+
+        assert_eq!(err.to_string(), "failed to solve: line 4: no such file or directory: /y")
+    }
+
+    proptest! {
+        // each case resolves every directory after every command
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn final_snapshot_matches_ground_truth(seed in any::<u64>()) {
+            let generated = generate(seed, &GeneratorOptions::default());
+            let watched = generated.directory_sizes.keys().collect::<Vec<_>>();
+            let timeline = Timeline::from_input(&generated.transcript, &TreeOptions::default(), &watched).unwrap();
+            let last = timeline.snapshots.last().unwrap();
+
+            prop_assert_eq!(last.total_size, generated.directory_sizes["/"]);
+            prop_assert_eq!(last.sizes.clone(), generated.directory_sizes.values().copied().map(Some).collect::<Vec<_>>());
+        }
+    }
+}
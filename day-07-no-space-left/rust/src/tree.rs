@@ -0,0 +1,981 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::BufRead;
+use std::path::Path;
+use aoc_common::{open, ParseError, Solution, TryLine};
+use crate::{Conflict, ConflictPolicy, Entry, Results, TreeError, TreeOptions, UnknownDirectoryPolicy};
+
+#[cfg(feature = "json")]
+mod json;
+
+/// A filesystem rebuilt from a terminal transcript, along with the total size of every directory
+pub struct Tree {
+    nodes: Vec<Node>,
+    current_index: usize,
+    options: TreeOptions,
+    line_number: usize,
+    warnings: Vec<Conflict>,
+    /// Whether directory sizes are kept up to date as lines are handled, rather than calculated at the end
+    incremental: bool
+}
+
+impl Tree {
+    const ROOT_INDEX: usize = 0;
+
+    /// Rebuilds the filesystem from the transcript `input`
+    pub fn from_input(input: &str, options: &TreeOptions) -> aoc_common::Result<Tree> {
+        Tree::from_lines(input.lines(), options)
+    }
+
+    /// Rebuilds the filesystem from the transcript at file path `filename`
+    pub fn from_file<P>(filename: P, options: &TreeOptions) -> aoc_common::Result<Tree>
+    where P: AsRef<Path> {
+        Tree::from_reader(open(filename)?, options)
+    }
+
+    /// Rebuilds the filesystem from a transcript read from `reader` line by line as it arrives, e.g. from stdin
+    pub fn from_reader<R>(reader: R, options: &TreeOptions) -> aoc_common::Result<Tree>
+    where R: BufRead {
+        Tree::from_lines(reader.lines(), options)
+    }
+
+    fn from_lines<I>(lines: I, options: &TreeOptions) -> aoc_common::Result<Tree>
+    where I: IntoIterator, I::Item: TryLine, aoc_common::Error: From<<I::Item as TryLine>::Error> {
+        let mut tree = Tree::with_options(options.clone());
+        for (i, l) in lines.into_iter().enumerate() {
+            let line = l.try_line()?;
+            tree.handle_line(line.as_ref()).map_err(|err| err.at_line(i + 1))?;
+        }
+
+        calc_directory_sizes(&mut tree.nodes)?;
+        tree.incremental = true;
+        Ok(tree)
+    }
+
+    /// An empty filesystem, holding just `/`, to be built up with [`Tree::push_line`]
+    pub fn incremental(options: &TreeOptions) -> Tree {
+        let mut tree = Tree::with_options(options.clone());
+        calc_directory_sizes(&mut tree.nodes).expect("an empty tree always has a size");
+        tree.incremental = true;
+        tree
+    }
+
+    /// Handles the next line of a transcript, updating the size of every directory it changes
+    ///
+    /// Lines can be pushed to any tree, so a tree rebuilt from one transcript can be carried on by another.
+    pub fn push_line(&mut self, line: &str) -> aoc_common::Result<()> {
+        self.handle_line(line).map_err(|err| err.at_line(self.line_number))
+    }
+
+    /// Works out the size of every directory again from the files it holds, returning the size of `/`
+    ///
+    /// Sizes are already up to date after rebuilding or pushing lines, so this is mostly useful for checking them.
+    pub fn recalculate_sizes(&mut self) -> Result<u64, TreeError> {
+        calc_directory_sizes(&mut self.nodes)
+    }
+
+    /// Number of transcript lines handled so far
+    pub fn line_number(&self) -> usize {
+        self.line_number
+    }
+
+    /// The root directory `/`
+    pub fn root(&self) -> DirectoryRef<'_> {
+        match &self.nodes[Tree::ROOT_INDEX] {
+            Node::Directory(dir) => DirectoryRef { tree: self, index: Tree::ROOT_INDEX, dir },
+            Node::File(_) => unreachable!("the root is always a directory")
+        }
+    }
+
+    /// Total size of every file in the filesystem
+    pub fn total_size(&self) -> u64 {
+        self.root().size()
+    }
+
+    /// Finds the file or directory at the absolute `path`, e.g. `/a/e` or `/d/k`
+    pub fn resolve(&self, path: &str) -> Option<NodeRef<'_>> {
+        let mut node = NodeRef::Directory(self.root());
+        for name in path.strip_prefix('/')?.split('/').filter(|name| !name.is_empty()) {
+            node = node.as_directory()?.child(name)?;
+        }
+
+        Some(node)
+    }
+
+    /// Conflicting listings that were ignored under [`ConflictPolicy::Warn`]
+    pub fn warnings(&self) -> &[Conflict] {
+        &self.warnings
+    }
+
+    fn node(&self, index: usize) -> Option<NodeRef<'_>> {
+        match self.nodes.get(index)? {
+            Node::Directory(dir) => Some(NodeRef::Directory(DirectoryRef { tree: self, index, dir })),
+            Node::File(file) => Some(NodeRef::File(FileRef { tree: self, file }))
+        }
+    }
+
+    fn with_options(options: TreeOptions) -> Tree {
+        let root_dir = Directory::new("/", None);
+        let root = Node::Directory(root_dir);
+        let nodes = vec![root];
+        Tree { nodes, current_index: Tree::ROOT_INDEX, options, line_number: 0, warnings: Vec::new(), incremental: false }
+    }
+
+    fn directory(&self, index: usize) -> Result<&Directory, TreeError> {
+        match self.nodes.get(index).ok_or(TreeError::NodeNotFound { index })? {
+            Node::Directory(dir) => Ok(dir),
+            Node::File(file) => Err(TreeError::NotADirectory { name: file.name.clone() })
+        }
+    }
+
+    fn current_directory(&self) -> Result<&Directory, TreeError> {
+        self.directory(self.current_index)
+    }
+
+    fn current_directory_mut(&mut self) -> Result<&mut Directory, TreeError> {
+        let index = self.current_index;
+        match self.nodes.get_mut(index).ok_or(TreeError::NodeNotFound { index })? {
+            Node::Directory(dir) => Ok(dir),
+            Node::File(file) => Err(TreeError::NotADirectory { name: file.name.clone() })
+        }
+    }
+
+    fn handle_line(&mut self, line: &str) -> Result<(), TreeError> {
+        self.line_number += 1;
+        // `lines` already drops `\r\n`, but a CRLF transcript cut off after its final `\r` leaves it behind
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let (first, rest) = line.split_once(' ')
+            .ok_or_else(|| ParseError::at(line, line, "`$ <command>`, `dir <name>` or `<size> <name>`").in_day(Results::DAY))?;
+
+        // parse errors from the handlers point into the part of the line they were given
+        let within = |err: TreeError, part: &str| match err {
+            TreeError::Parse(err) => TreeError::Parse(err.within(line, part).in_day(Results::DAY)),
+            err => err
+        };
+
+        match first {
+            "$" => self.handle_command(rest).map_err(|err| within(err, rest))?,
+            "dir" => self.handle_dir(rest)?,
+            _ => self.handle_file(first, rest).map_err(|err| within(err, first))?
+        }
+
+        Ok(())
+    }
+
+    fn handle_command(&mut self, command: &str) -> Result<(), TreeError> {
+        // the argument is the rest of the line, so names may contain spaces
+        let (cmd, argument) = match command.split_once(' ') {
+            Some((cmd, argument)) => (cmd, Some(argument)),
+            None => (command, None)
+        };
+
+        match (cmd, argument) {
+            ("ls", _) => Ok(()),
+            ("cd" | "mkdir" | "touch" | "rm", None) => Err(ParseError::at_end(command, "path").into()),
+            ("cd", Some(path)) => self.cd(path),
+            ("mkdir", Some(path)) => self.mkdir(path),
+            ("touch", Some(argument)) => self.touch(argument),
+            ("rm", Some(argument)) => self.rm(argument),
+            _ => Err(TreeError::UnknownCommand { command: command.to_string() })
+        }
+    }
+
+    /// Enters the directory at `path`, e.g. `a`, `..`, `/` or `/a/b/c`
+    fn cd(&mut self, path: &str) -> Result<(), TreeError> {
+        let start_index = self.current_index;
+        if path.starts_with('/') {
+            self.current_index = Tree::ROOT_INDEX;
+        }
+
+        for name in path.split('/').filter(|name| !name.is_empty() && *name != ".") {
+            match name {
+                ".." => {
+                    let current_directory = self.current_directory()?;
+                    self.current_index = current_directory.parent_index.ok_or(TreeError::CdAboveRoot)?;
+                },
+
+                _ => if !self.cd_child(name)? {
+                    // the whole `cd` is ignored, not just the rest of the path
+                    self.current_index = start_index;
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Enters the directory `name`, returning false if it's unknown and ignored
+    fn cd_child(&mut self, name: &str) -> Result<bool, TreeError> {
+        match self.find_child(self.current_index, name) {
+            Ok(child_index) => self.current_index = child_index,
+
+            Err(TreeError::UnknownDirectory { .. }) if self.options.unknown_directory == UnknownDirectoryPolicy::Create => {
+                self.handle_dir(name)?;
+                self.current_index = self.nodes.len() - 1;
+            },
+
+            Err(TreeError::UnknownDirectory { .. }) if self.options.unknown_directory == UnknownDirectoryPolicy::Ignore => return Ok(false),
+
+            Err(err) => return Err(err)
+        }
+
+        Ok(true)
+    }
+
+    /// Creates the directory at `path`, doing nothing if it already exists
+    fn mkdir(&mut self, path: &str) -> Result<(), TreeError> {
+        let (index, name) = self.split_path(path)?;
+        self.in_directory(index, |tree| tree.handle_dir(name))
+    }
+
+    /// Handles `touch <size> <path>`, creating a file or changing the size of an existing file
+    fn touch(&mut self, argument: &str) -> Result<(), TreeError> {
+        let (size_str, path) = argument.split_once(' ').ok_or_else(|| ParseError::at_end(argument, "path"))?;
+        let size = size_str.parse::<u64>()
+            .map_err(|_| ParseError::at(argument, size_str, "file size"))?;
+
+        let (index, name) = self.split_path(path)?;
+        match self.find_entry(index, name)?.map(|child_index| (child_index, self.nodes.get_mut(child_index))) {
+            Some((child_index, Some(Node::File(file)))) => {
+                let old_size = file.size;
+                self.resize(index, old_size, size)?;
+                if let Some(Node::File(file)) = self.nodes.get_mut(child_index) {
+                    file.size = size;
+                }
+                Ok(())
+            },
+            Some(_) => Err(TreeError::IsADirectory { path: join_path(&directory_path(&self.nodes, index), name) }),
+            None => self.in_directory(index, |tree| tree.add_file(name, size))
+        }
+    }
+
+    /// Handles `rm [-r] [-f] <path>`, removing a file, or a directory and its contents with `-r`
+    fn rm(&mut self, argument: &str) -> Result<(), TreeError> {
+        let (mut recursive, mut force) = (false, false);
+        let mut path = argument;
+        while let Some((option, rest)) = path.split_once(' ').filter(|(option, _)| option.starts_with('-')) {
+            for flag in option.chars().skip(1) {
+                match flag {
+                    'r' | 'R' => recursive = true,
+                    'f' => force = true,
+                    _ => return Err(ParseError::at(argument, option, "option `-r` or `-f`").into())
+                }
+            }
+            path = rest;
+        }
+
+        let (index, name) = self.split_path(path)?;
+        let full_path = join_path(&directory_path(&self.nodes, index), name);
+        let child_index = match self.find_entry(index, name)? {
+            Some(child_index) => child_index,
+            None if force => return Ok(()),
+            None => return Err(TreeError::NoSuchEntry { path: full_path })
+        };
+
+        let removed_size = match self.nodes.get(child_index) {
+            Some(Node::Directory(dir)) => dir.size.unwrap_or_default(),
+            Some(Node::File(file)) => file.size,
+            None => return Err(TreeError::NodeNotFound { index: child_index })
+        };
+
+        if let Some(Node::Directory(_)) = self.nodes.get(child_index) {
+            if !recursive {
+                return Err(TreeError::IsADirectory { path: full_path });
+            }
+
+            let mut current = Some(self.current_index);
+            while let Some(ancestor) = current {
+                if ancestor == child_index {
+                    return Err(TreeError::InUse { path: full_path });
+                }
+                current = self.directory(ancestor)?.parent_index;
+            }
+        }
+
+        self.resize(index, removed_size, 0)?;
+
+        // the removed nodes stay in `nodes`, but can't be reached from the root
+        let directory = match self.nodes.get_mut(index) {
+            Some(Node::Directory(dir)) => dir,
+            _ => return Err(TreeError::NodeNotFound { index })
+        };
+        directory.children_indices.retain(|i| *i != child_index);
+        directory.children_by_name.remove(name);
+
+        Ok(())
+    }
+
+    /// Splits `path` into the index of the directory holding it and the name within that directory
+    fn split_path<'p>(&self, path: &'p str) -> Result<(usize, &'p str), TreeError> {
+        let trimmed = path.trim_end_matches('/');
+        let (index, name) = match trimmed.rsplit_once('/') {
+            Some(("", name)) => (Tree::ROOT_INDEX, name),
+            Some((directory, name)) => (self.resolve_directory(directory)?, name),
+            None => (self.current_index, trimmed)
+        };
+
+        match name {
+            "" | "." | ".." => Err(TreeError::InvalidPath { path: path.to_string() }),
+            _ => Ok((index, name))
+        }
+    }
+
+    /// Finds the directory at `path` without moving to it
+    fn resolve_directory(&self, path: &str) -> Result<usize, TreeError> {
+        let mut index = if path.starts_with('/') { Tree::ROOT_INDEX } else { self.current_index };
+
+        for name in path.split('/').filter(|name| !name.is_empty() && *name != ".") {
+            index = match name {
+                ".." => self.directory(index)?.parent_index.ok_or(TreeError::CdAboveRoot)?,
+                _ => self.find_child(index, name)?
+            };
+        }
+
+        Ok(index)
+    }
+
+    /// Replaces `old_size` bytes in the directory at `index` with `new_size` bytes, updating it and its ancestors
+    /// when sizes are kept up to date
+    fn resize(&mut self, index: usize, old_size: u64, new_size: u64) -> Result<(), TreeError> {
+        if !self.incremental || old_size == new_size {
+            return Ok(());
+        }
+
+        // every size is checked before any is changed, so an overflow leaves the tree as it was
+        let mut updates = Vec::new();
+        let mut current = Some(index);
+        while let Some(dir_index) = current {
+            let dir = self.directory(dir_index)?;
+            let size = dir.get_size()?.saturating_sub(old_size).checked_add(new_size)
+                .ok_or_else(|| TreeError::SizeOverflow { path: directory_path(&self.nodes, dir_index) })?;
+            updates.push((dir_index, size));
+            current = dir.parent_index;
+        }
+
+        for (dir_index, size) in updates {
+            if let Some(Node::Directory(dir)) = self.nodes.get_mut(dir_index) {
+                dir.size = Some(size);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs `f` as if the directory at `index` were the current directory
+    fn in_directory<T>(&mut self, index: usize, f: impl FnOnce(&mut Tree) -> Result<T, TreeError>) -> Result<T, TreeError> {
+        let current_index = self.current_index;
+        self.current_index = index;
+        let result = f(self);
+        self.current_index = current_index;
+        result
+    }
+
+    fn find_child(&self, index: usize, name: &str) -> Result<usize, TreeError> {
+        let child_index = self.find_entry(index, name)?.ok_or_else(|| TreeError::UnknownDirectory { name: name.to_string() })?;
+        match self.nodes.get(child_index).ok_or(TreeError::NodeNotFound { index: child_index })? {
+            Node::Directory(_) => Ok(child_index),
+            Node::File(_) => Err(TreeError::NotADirectory { name: name.to_string() })
+        }
+    }
+
+    fn handle_dir(&mut self, name: &str) -> Result<(), TreeError> {
+        check_name(name)?;
+        if let Some(existing) = self.existing_entry(name)? {
+            return match existing {
+                Entry::Directory => Ok(()),
+                Entry::File { .. } => self.conflict(name, existing, Entry::Directory)
+            };
+        }
+
+        let mut dir = Directory::new(name, Some(self.current_index));
+        if self.incremental {
+            dir.size = Some(0);
+        }
+
+        self.push(Node::Directory(dir))?;
+
+        Ok(())
+    }
+
+    fn handle_file(&mut self, size_str: &str, name: &str) -> Result<(), TreeError> {
+        let size = size_str.parse::<u64>()
+            .map_err(|_| ParseError::at(size_str, size_str, "file size or `dir`"))?;
+
+        self.add_file(name, size)
+    }
+
+    fn add_file(&mut self, name: &str, size: u64) -> Result<(), TreeError> {
+        check_name(name)?;
+        if let Some(existing) = self.existing_entry(name)? {
+            return match existing {
+                Entry::File { size: existing_size } if existing_size == size => Ok(()),
+                _ => self.conflict(name, existing, Entry::File { size })
+            };
+        }
+
+        self.resize(self.current_index, 0, size)?;
+        let file = File { name: name.to_string(), parent_index: self.current_index, size };
+
+        self.push(Node::File(file))?;
+
+        Ok(())
+    }
+
+    /// Finds the entry named `name` listed earlier in the current directory
+    fn existing_entry(&self, name: &str) -> Result<Option<Entry>, TreeError> {
+        let entry = match self.find_entry(self.current_index, name)?.and_then(|index| self.nodes.get(index)) {
+            Some(Node::Directory(_)) => Some(Entry::Directory),
+            Some(Node::File(file)) => Some(Entry::File { size: file.size }),
+            None => None
+        };
+
+        Ok(entry)
+    }
+
+    /// Finds the index of the file or directory named `name` in the directory at `index`
+    fn find_entry(&self, index: usize, name: &str) -> Result<Option<usize>, TreeError> {
+        let directory = self.directory(index)?;
+
+        Ok(directory.children_by_name.get(name).copied())
+    }
+
+    fn conflict(&mut self, name: &str, existing: Entry, listed: Entry) -> Result<(), TreeError> {
+        let path = join_path(&directory_path(&self.nodes, self.current_index), name);
+        let conflict = Conflict { line: self.line_number, path, existing, listed };
+
+        match self.options.conflict {
+            ConflictPolicy::Warn => {
+                self.warnings.push(conflict);
+                Ok(())
+            },
+            ConflictPolicy::Error => Err(TreeError::Conflict(conflict))
+        }
+    }
+
+    fn push(&mut self, node: Node) -> Result<(), TreeError> {
+        let index = self.nodes.len();
+        let name = match &node {
+            Node::Directory(dir) => dir.name.clone(),
+            Node::File(file) => file.name.clone()
+        };
+
+        let current_directory = self.current_directory_mut()?;
+        current_directory.children_indices.push(index);
+        current_directory.children_by_name.insert(name, index);
+        self.nodes.push(node);
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Tree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tree").field("root", &self.root()).field("nodes", &self.nodes.len()).finish()
+    }
+}
+
+/// A file or directory in a [`Tree`]
+#[derive(Debug, Clone, Copy)]
+pub enum NodeRef<'a> {
+    Directory(DirectoryRef<'a>),
+    File(FileRef<'a>)
+}
+
+impl<'a> NodeRef<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            NodeRef::Directory(dir) => dir.name(),
+            NodeRef::File(file) => file.name()
+        }
+    }
+
+    /// Absolute path, e.g. `/a/e`
+    pub fn path(&self) -> String {
+        match self {
+            NodeRef::Directory(dir) => dir.path(),
+            NodeRef::File(file) => file.path()
+        }
+    }
+
+    /// Size of a file, or total size of a directory
+    pub fn size(&self) -> u64 {
+        match self {
+            NodeRef::Directory(dir) => dir.size(),
+            NodeRef::File(file) => file.size()
+        }
+    }
+
+    /// The directory containing this node, or `None` for the root
+    pub fn parent(&self) -> Option<DirectoryRef<'a>> {
+        match self {
+            NodeRef::Directory(dir) => dir.parent(),
+            NodeRef::File(file) => Some(file.parent())
+        }
+    }
+
+    pub fn as_directory(self) -> Option<DirectoryRef<'a>> {
+        match self {
+            NodeRef::Directory(dir) => Some(dir),
+            NodeRef::File(_) => None
+        }
+    }
+
+    pub fn as_file(self) -> Option<FileRef<'a>> {
+        match self {
+            NodeRef::Directory(_) => None,
+            NodeRef::File(file) => Some(file)
+        }
+    }
+}
+
+/// A directory in a [`Tree`]
+#[derive(Clone, Copy)]
+pub struct DirectoryRef<'a> {
+    tree: &'a Tree,
+    index: usize,
+    dir: &'a Directory
+}
+
+impl<'a> DirectoryRef<'a> {
+    /// Name of the directory, which is `/` for the root
+    pub fn name(&self) -> &'a str {
+        &self.dir.name
+    }
+
+    /// Absolute path, e.g. `/a/e`
+    pub fn path(&self) -> String {
+        directory_path(&self.tree.nodes, self.index)
+    }
+
+    /// Total size of the files in the directory and its subdirectories
+    pub fn size(&self) -> u64 {
+        // sizes are calculated as soon as the tree is rebuilt
+        self.dir.get_size().unwrap_or_default()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.dir.parent_index.is_none()
+    }
+
+    /// The directory containing this directory, or `None` for the root
+    pub fn parent(&self) -> Option<DirectoryRef<'a>> {
+        self.tree.node(self.dir.parent_index?)?.as_directory()
+    }
+
+    /// Files and directories directly in this directory, in the order they were listed
+    pub fn children(&self) -> impl Iterator<Item = NodeRef<'a>> + 'a {
+        let tree = self.tree;
+        self.dir.children_indices.iter().filter_map(move |index| tree.node(*index))
+    }
+
+    /// The file or directory named `name` directly in this directory
+    pub fn child(&self, name: &str) -> Option<NodeRef<'a>> {
+        self.dir.children_by_name.get(name).and_then(|index| self.tree.node(*index))
+    }
+
+    /// The file named `name` directly in this directory
+    pub fn file(&self, name: &str) -> Option<FileRef<'a>> {
+        self.child(name)?.as_file()
+    }
+
+    /// The directory named `name` directly in this directory
+    pub fn directory(&self, name: &str) -> Option<DirectoryRef<'a>> {
+        self.child(name)?.as_directory()
+    }
+
+    /// This directory followed by everything below it, depth-first in listing order
+    pub fn walk(&self) -> Walk<'a> {
+        Walk { stack: vec![NodeRef::Directory(*self)] }
+    }
+}
+
+impl fmt::Debug for DirectoryRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DirectoryRef").field("path", &self.path()).field("size", &self.size()).finish()
+    }
+}
+
+/// A file in a [`Tree`]
+#[derive(Clone, Copy)]
+pub struct FileRef<'a> {
+    tree: &'a Tree,
+    file: &'a File
+}
+
+impl<'a> FileRef<'a> {
+    pub fn name(&self) -> &'a str {
+        &self.file.name
+    }
+
+    /// Absolute path, e.g. `/a/f`
+    pub fn path(&self) -> String {
+        join_path(&self.parent().path(), self.name())
+    }
+
+    pub fn size(&self) -> u64 {
+        self.file.size
+    }
+
+    /// The directory containing this file
+    pub fn parent(&self) -> DirectoryRef<'a> {
+        self.tree.node(self.file.parent_index)
+            .and_then(NodeRef::as_directory)
+            .unwrap_or_else(|| self.tree.root())
+    }
+}
+
+impl fmt::Debug for FileRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FileRef").field("path", &self.path()).field("size", &self.size()).finish()
+    }
+}
+
+/// Depth-first iterator over a directory and everything below it, see [`DirectoryRef::walk`]
+pub struct Walk<'a> {
+    stack: Vec<NodeRef<'a>>
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = NodeRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        if let NodeRef::Directory(dir) = node {
+            let children = dir.children().collect::<Vec<_>>();
+            self.stack.extend(children.into_iter().rev());
+        }
+
+        Some(node)
+    }
+}
+
+enum Node {
+    Directory(Directory),
+    File(File)
+}
+
+struct Directory {
+    name: String,
+    parent_index: Option<usize>,
+    children_indices: Vec<usize>,
+    /// Index of each child by name, so entries are found without scanning the listing
+    children_by_name: HashMap<String, usize>,
+    size: Option<u64>
+}
+
+impl Directory {
+    fn new(name: &str, parent_index: Option<usize>) -> Directory {
+        Directory {
+            name: name.to_string(),
+            parent_index,
+            children_indices: Vec::new(),
+            children_by_name: HashMap::new(),
+            size: None
+        }
+    }
+
+    fn get_size(&self) -> Result<u64, TreeError> {
+        self.size.ok_or_else(|| TreeError::SizeNotComputed { name: self.name.clone() })
+    }
+}
+
+struct File {
+    name: String,
+    parent_index: usize,
+    size: u64
+}
+
+/// Absolute path of the directory at `index` in `nodes`, e.g. `/a/e`
+fn directory_path(nodes: &[Node], index: usize) -> String {
+    let mut names = Vec::new();
+    let mut current = index;
+    while let Some(Node::Directory(Directory { name, parent_index: Some(parent_index), .. })) = nodes.get(current) {
+        names.push(name.as_str());
+        current = *parent_index;
+    }
+
+    if names.is_empty() {
+        String::from("/")
+    } else {
+        names.iter().rev().map(|name| format!("/{name}")).collect()
+    }
+}
+
+/// Checks that a listed `name` names a single entry, like `split_path` does for the last part of a path
+fn check_name(name: &str) -> Result<(), TreeError> {
+    if matches!(name, "" | "." | "..") || name.contains('/') {
+        return Err(TreeError::InvalidPath { path: name.to_string() });
+    }
+
+    Ok(())
+}
+
+/// Path of the entry `name` in the directory at `directory`
+pub(crate) fn join_path(directory: &str, name: &str) -> String {
+    match directory {
+        "/" => format!("/{name}"),
+        _ => format!("{directory}/{name}")
+    }
+}
+
+/// Works out the total size of every directory reachable from `/`, returning the size of `/`
+///
+/// Directories are summed in post-order with a stack of the directories part way through,
+/// rather than by recursing, so deep trees can't overflow the call stack.
+fn calc_directory_sizes(nodes: &mut [Node]) -> Result<u64, TreeError> {
+    // each directory being summed, with the position of its next child and the size of the children so far
+    let mut stack: Vec<(usize, usize, u64)> = vec![(0, 0, 0)];
+    while let Some((index, position, size)) = stack.last_mut() {
+        let dir = match nodes.get(*index).ok_or(TreeError::NodeNotFound { index: *index })? {
+            Node::Directory(dir) => dir,
+            Node::File(file) => return Err(TreeError::NotADirectory { name: file.name.clone() })
+        };
+
+        // add up files until reaching a subdirectory, which is summed before carrying on
+        let mut subdirectory = None;
+        while let Some(child_index) = dir.children_indices.get(*position) {
+            *position += 1;
+            match nodes.get(*child_index).ok_or(TreeError::NodeNotFound { index: *child_index })? {
+                Node::Directory(_) => {
+                    subdirectory = Some(*child_index);
+                    break;
+                },
+                Node::File(file) => *size = size.checked_add(file.size)
+                    .ok_or_else(|| TreeError::SizeOverflow { path: directory_path(nodes, *index) })?
+            }
+        }
+
+        if let Some(child_index) = subdirectory {
+            stack.push((child_index, 0, 0));
+            continue;
+        }
+
+        let (index, _, size) = stack.pop().unwrap_or_default();
+        if let Some(Node::Directory(dir)) = nodes.get_mut(index) {
+            dir.size = Some(size);
+        }
+
+        match stack.last_mut() {
+            Some((parent_index, _, parent_size)) => *parent_size = parent_size.checked_add(size)
+                .ok_or_else(|| TreeError::SizeOverflow { path: directory_path(nodes, *parent_index) })?,
+            None => return Ok(size)
+        }
+    }
+
+    unreachable!("the root is always summed last")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::EXAMPLE;
+    use crate::{Conflict, ConflictPolicy, Entry, TreeError, TreeOptions, UnknownDirectoryPolicy};
+    use super::{calc_directory_sizes, Directory, NodeRef, Tree};
+
+    fn tree_error(transcript: &str) -> TreeError {
+        let options = TreeOptions { unknown_directory: UnknownDirectoryPolicy::Strict, conflict: ConflictPolicy::Error };
+        let mut tree = Tree::with_options(options);
+        for line in transcript.lines() {
+            if let Err(err) = tree.handle_line(line) {
+                return err;
+            }
+        }
+
+        calc_directory_sizes(&mut tree.nodes).expect_err("expected transcript to fail")
+    }
+
+    #[test]
+    fn unknown_command() {
+        let err = tree_error("$ cd /\n$ mv a b\n");
+
+        assert_eq!(err, TreeError::UnknownCommand { command: String::from("mv a b") });
+        assert_eq!(tree_error("$ cd /\n$ pwd\n"), TreeError::UnknownCommand { command: String::from("pwd") })
+    }
+
+    #[test]
+    fn cd_unknown_directory() {
+        let err = tree_error("$ cd /\n$ ls\ndir a\n$ cd b\n");
+
+        assert_eq!(err, TreeError::UnknownDirectory { name: String::from("b") })
+    }
+
+    #[test]
+    fn conflict_file_listed_as_directory() {
+        let err = tree_error("$ cd /\n$ ls\n100 a\n$ ls\ndir a\n");
+
+        assert!(matches!(err, TreeError::Conflict(Conflict { existing: Entry::File { size: 100 }, listed: Entry::Directory, .. })))
+    }
+
+    #[test]
+    fn cd_above_root() {
+        let err = tree_error("$ cd /\n$ cd ..\n");
+
+        assert_eq!(err, TreeError::CdAboveRoot)
+    }
+
+    #[test]
+    fn cd_into_file() {
+        let err = tree_error("$ cd /\n$ ls\n100 f.txt\n$ cd f.txt\n");
+
+        assert_eq!(err, TreeError::NotADirectory { name: String::from("f.txt") })
+    }
+
+    #[test]
+    fn size_not_computed() {
+        let dir = Directory::new("a", None);
+
+        assert_eq!(dir.get_size(), Err(TreeError::SizeNotComputed { name: String::from("a") }))
+    }
+
+    #[test]
+    fn size_overflow() {
+        let err = tree_error("$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\ndir b\n$ cd b\n$ ls\n18446744073709551615 x\n1 y\n");
+
+        assert_eq!(err, TreeError::SizeOverflow { path: String::from("/a/b") })
+    }
+
+    #[test]
+    fn file_size_too_large() {
+        let err = tree_error("$ cd /\n$ ls\n18446744073709551616 a\n");
+
+        assert!(matches!(err, TreeError::Parse(_)))
+    }
+
+    #[test]
+    fn negative_file_size() {
+        let err = tree_error("$ cd /\n$ ls\n-1 a\n");
+
+        assert!(matches!(err, TreeError::Parse(_)))
+    }
+
+    #[test]
+    fn resolve_paths() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let sizes = ["/", "/a", "/a/e", "/a/e/i", "/d/", "/b.txt"].map(|path| tree.resolve(path).map(|node| node.size()));
+
+        assert_eq!(sizes, [Some(48_381_165), Some(94_853), Some(584), Some(584), Some(24_933_642), Some(14_848_514)])
+    }
+
+    #[test]
+    fn resolve_missing_paths() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+
+        assert!(["/x", "/b.txt/x", "a", ""].iter().all(|path| tree.resolve(path).is_none()))
+    }
+
+    #[test]
+    fn children_in_listing_order() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let names = tree.root().children().map(|child| child.name()).collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["a", "b.txt", "c.dat", "d"])
+    }
+
+    #[test]
+    fn walk_depth_first() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let paths = tree.root().directory("a").unwrap().walk().map(|node| node.path()).collect::<Vec<_>>();
+
+        assert_eq!(paths, vec!["/a", "/a/e", "/a/e/i", "/a/f", "/a/g", "/a/h.lst"])
+    }
+
+    #[test]
+    fn parent_navigation() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let file = tree.resolve("/a/e/i").and_then(NodeRef::as_file).unwrap();
+        let parent = file.parent();
+
+        assert_eq!((parent.path(), parent.parent().map(|dir| dir.name())), (String::from("/a/e"), Some("a")));
+        assert!(tree.root().is_root() && tree.root().parent().is_none())
+    }
+
+    #[test]
+    fn file_lookup() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let d = tree.root().directory("d").unwrap();
+
+        assert_eq!(d.file("k").map(|file| file.size()), Some(7_214_296));
+        assert!(d.file("a").is_none() && tree.root().file("a").is_none())
+    }
+
+    #[test]
+    fn cd_paths() {
+        let tree = Tree::from_input("$ cd /\n$ mkdir a\n$ mkdir a/b\n$ cd /a/b\n$ touch 5 x\n$ cd ../../a/./b/\n$ touch 7 y\n", &TreeOptions::default()).unwrap();
+
+        assert_eq!(tree.resolve("/a/b").map(|node| node.size()), Some(12))
+    }
+
+    #[test]
+    fn cd_ignored_path() {
+        let options = TreeOptions { unknown_directory: UnknownDirectoryPolicy::Ignore, ..TreeOptions::default() };
+        let tree = Tree::from_input("$ cd /\n$ mkdir a\n$ cd a/b\n$ ls\n5 x\n", &options).unwrap();
+
+        // the whole cd is skipped, so x is listed in /
+        assert_eq!(tree.resolve("/x").map(|node| node.size()), Some(5))
+    }
+
+    #[test]
+    fn mkdir_and_touch() {
+        let tree = Tree::from_input("$ cd /\n$ mkdir a\n$ mkdir a\n$ touch 10 /a/x\n$ touch 3 a/y\n$ touch 4 /a/x\n", &TreeOptions::default()).unwrap();
+        let names = tree.root().walk().map(|node| node.path()).collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["/", "/a", "/a/x", "/a/y"]);
+        assert_eq!(tree.total_size(), 7)
+    }
+
+    #[test]
+    fn rm() {
+        let tree = Tree::from_input(&format!("{EXAMPLE}$ rm /d/k\n$ rm -r /a\n$ rm -f /missing\n"), &TreeOptions::default()).unwrap();
+
+        assert_eq!(tree.total_size(), 14_848_514 + 8_504_156 + 4_060_174 + 8_033_020 + 5_626_152);
+        assert!(tree.resolve("/a").is_none() && tree.resolve("/d/k").is_none())
+    }
+
+    #[test]
+    fn rm_then_relist() {
+        let tree = Tree::from_input("$ cd /\n$ ls\ndir a\n$ rm -r a\n$ ls\ndir a\n$ cd a\n$ ls\n5 x\n", &TreeOptions::default()).unwrap();
+
+        assert_eq!(tree.total_size(), 5)
+    }
+
+    #[test]
+    fn rm_errors() {
+        assert_eq!(tree_error("$ cd /\n$ rm a\n"), TreeError::NoSuchEntry { path: String::from("/a") });
+        assert_eq!(tree_error("$ cd /\n$ mkdir a\n$ rm a\n"), TreeError::IsADirectory { path: String::from("/a") });
+        assert_eq!(tree_error("$ cd /\n$ mkdir a\n$ mkdir a/b\n$ cd a/b\n$ rm -r /a\n"), TreeError::InUse { path: String::from("/a") });
+        assert_eq!(tree_error("$ cd /\n$ rm -r ..\n"), TreeError::InvalidPath { path: String::from("..") });
+        assert!(matches!(tree_error("$ cd /\n$ rm -x a\n"), TreeError::Parse(_)))
+    }
+
+    #[test]
+    fn ls_invalid_names() {
+        let errors = ["dir a/b", "dir .", "dir ..", "5 .", "5 ..", "5 /"]
+            .map(|listing| tree_error(&format!("$ cd /\n$ ls\n{listing}\n")));
+
+        assert_eq!(errors, ["a/b", ".", "..", ".", "..", "/"].map(|path| TreeError::InvalidPath { path: path.to_string() }))
+    }
+
+    #[test]
+    fn touch_errors() {
+        assert_eq!(tree_error("$ cd /\n$ mkdir a\n$ touch 5 a\n"), TreeError::IsADirectory { path: String::from("/a") });
+        assert_eq!(tree_error("$ cd /\n$ touch 5 b/x\n"), TreeError::UnknownDirectory { name: String::from("b") });
+        assert!(matches!(tree_error("$ cd /\n$ touch x\n"), TreeError::Parse(_)))
+    }
+
+    #[test]
+    fn deep_tree() {
+        let transcript = "$ ls\ndir d\n1 f\n$ cd d\n".repeat(100_000);
+        let mut tree = Tree::from_input(&transcript, &TreeOptions::default()).unwrap();
+
+        assert_eq!(tree.total_size(), 100_000);
+        assert_eq!(tree.recalculate_sizes(), Ok(100_000))
+    }
+
+    #[test]
+    fn removed_directory_overflow() {
+        let transcript = "$ cd /\n$ mkdir a\n$ cd a\n$ ls\n18446744073709551615 x\n1 y\n$ cd /\n$ rm -r a\n";
+        let tree = Tree::from_input(transcript, &TreeOptions::default()).unwrap();
+
+        assert_eq!(tree.total_size(), 0)
+    }
+}
@@ -0,0 +1,345 @@
+use std::{fmt, mem};
+use serde::de::{Error as _, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use aoc_common::{Error, ParseError};
+use crate::{ConflictPolicy, DirectoryRef, NodeRef, TreeOptions};
+use super::{calc_directory_sizes, Tree};
+
+/// A file or directory as written to JSON, e.g. `{"type": "file", "name": "b.txt", "size": 14848514}`
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum JsonNode {
+    #[serde(rename = "dir")]
+    Directory {
+        name: String,
+        /// Total size, which is checked against the contents when present
+        #[serde(skip_serializing_if = "Option::is_none")]
+        size: Option<u64>,
+        children: Vec<JsonNode>
+    },
+
+    #[serde(rename = "file")]
+    File { name: String, size: u64 }
+}
+
+// dropping the children one directory level at a time would recurse once per level
+impl Drop for JsonNode {
+    fn drop(&mut self) {
+        let mut pending = Vec::new();
+        if let JsonNode::Directory { children, .. } = self {
+            pending.append(children);
+        }
+
+        while let Some(mut node) = pending.pop() {
+            if let JsonNode::Directory { children, .. } = &mut node {
+                pending.append(children);
+            }
+        }
+    }
+}
+
+impl From<NodeRef<'_>> for JsonNode {
+    fn from(node: NodeRef) -> Self {
+        let dir = match node {
+            NodeRef::Directory(dir) => dir,
+            NodeRef::File(file) => return JsonNode::File { name: file.name().to_string(), size: file.size() }
+        };
+
+        // each directory is finished once all of its children are, then handed to its parent
+        let mut pending = vec![(dir, dir.children(), Vec::new())];
+        loop {
+            let (_, children, nodes) = pending.last_mut().expect("the root directory is only finished by returning");
+            match children.next() {
+                Some(NodeRef::Directory(child)) => pending.push((child, child.children(), Vec::new())),
+                Some(NodeRef::File(file)) => nodes.push(JsonNode::File { name: file.name().to_string(), size: file.size() }),
+                None => {
+                    let (dir, _, children) = pending.pop().expect("the last directory was just read");
+                    let node = JsonNode::Directory { name: dir.name().to_string(), size: Some(dir.size()), children };
+                    match pending.last_mut() {
+                        Some((_, _, nodes)) => nodes.push(node),
+                        None => return node
+                    }
+                }
+            }
+        }
+    }
+}
+
+// `#[serde(tag = "type")]` would buffer every node and read its children back recursively, so the fields
+// are read by hand, in any order, straight from the deserializer
+impl<'de> Deserialize<'de> for JsonNode {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        deserializer.deserialize_map(JsonNodeVisitor)
+    }
+}
+
+struct JsonNodeVisitor;
+
+impl<'de> Visitor<'de> for JsonNodeVisitor {
+    type Value = JsonNode;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a file or directory")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<JsonNode, A::Error>
+    where A: MapAccess<'de> {
+        let (mut node_type, mut name, mut size, mut children) = (None, None, None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" => node_type = Some(map.next_value::<String>()?),
+                "name" => name = Some(map.next_value::<String>()?),
+                "size" => size = map.next_value::<Option<u64>>()?,
+                "children" => children = Some(map.next_value::<Vec<JsonNode>>()?),
+                _ => { map.next_value::<IgnoredAny>()?; }
+            }
+        }
+
+        let name = name.ok_or_else(|| A::Error::missing_field("name"))?;
+        match node_type.as_deref() {
+            Some("dir") => Ok(JsonNode::Directory { name, size, children: children.unwrap_or_default() }),
+            Some("file") => Ok(JsonNode::File { name, size: size.ok_or_else(|| A::Error::missing_field("size"))? }),
+            Some(node_type) => Err(A::Error::unknown_variant(node_type, &["dir", "file"])),
+            None => Err(A::Error::missing_field("type"))
+        }
+    }
+}
+
+impl Tree {
+    /// Writes the filesystem as nested, pretty-printed JSON
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Rebuilds a filesystem written by [`Tree::to_json`]
+    ///
+    /// Directory sizes are recalculated, and must match the sizes in `json` when they are given. Trees nested
+    /// deeper than serde_json's usual limit of 128 levels are read on a stack that grows as needed.
+    pub fn from_json(json: &str) -> aoc_common::Result<Tree> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        deserializer.disable_recursion_limit();
+        let root = JsonNode::deserialize(serde_stacker::Deserializer::new(&mut deserializer))
+            .and_then(|root| deserializer.end().map(|()| root))
+            .map_err(|err| parse_error(json, &err))?;
+        Tree::from_json_node(root)
+    }
+
+    fn from_json_node(mut root: JsonNode) -> aoc_common::Result<Tree> {
+        let JsonNode::Directory { size, children, .. } = &mut root else {
+            return Err(Error::solve("the root of the tree must be a directory"));
+        };
+        let (size, children) = (*size, mem::take(children));
+
+        // entries listed twice must agree, just like entries listed twice in a transcript
+        let mut tree = Tree::with_options(TreeOptions { conflict: ConflictPolicy::Error, ..TreeOptions::default() });
+        let mut declared_sizes = vec![(Tree::ROOT_INDEX, size)];
+        tree.import_children(Tree::ROOT_INDEX, children, &mut declared_sizes)?;
+        calc_directory_sizes(&mut tree.nodes)?;
+        tree.incremental = true;
+
+        for (index, declared_size) in declared_sizes {
+            let dir = tree.node(index).and_then(NodeRef::as_directory);
+            if let (Some(dir), Some(declared_size)) = (dir, declared_size) {
+                check_size(dir, declared_size)?;
+            }
+        }
+
+        Ok(tree)
+    }
+
+    fn import_children(&mut self, index: usize, children: Vec<JsonNode>, declared_sizes: &mut Vec<(usize, Option<u64>)>) -> aoc_common::Result<()> {
+        let mut pending = vec![(index, children.into_iter())];
+        while let Some((index, children)) = pending.last_mut() {
+            let index = *index;
+            let Some(mut child) = children.next() else {
+                pending.pop();
+                continue;
+            };
+
+            self.current_index = index;
+            match &mut child {
+                JsonNode::Directory { name, size, children } => {
+                    check_name(name)?;
+                    self.handle_dir(name)?;
+                    let child_index = self.find_child(index, name)?;
+                    declared_sizes.push((child_index, *size));
+                    pending.push((child_index, mem::take(children).into_iter()));
+                },
+
+                JsonNode::File { name, size } => {
+                    check_name(name)?;
+                    self.add_file(name, *size)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Serialize for Tree {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where S: Serializer {
+        JsonNode::from(NodeRef::Directory(self.root())).serialize(serde_stacker::Serializer::new(serializer))
+    }
+}
+
+impl<'de> Deserialize<'de> for Tree {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where D: Deserializer<'de> {
+        let root = JsonNode::deserialize(serde_stacker::Deserializer::new(deserializer))?;
+        Tree::from_json_node(root).map_err(D::Error::custom)
+    }
+}
+
+fn check_name(name: &str) -> aoc_common::Result<()> {
+    if name.is_empty() || name.contains('/') {
+        return Err(Error::solve(format!("invalid file or directory name: `{name}`")));
+    }
+
+    Ok(())
+}
+
+fn check_size(dir: DirectoryRef, declared_size: u64) -> aoc_common::Result<()> {
+    if dir.size() != declared_size {
+        return Err(Error::solve(format!("{} has size {declared_size} but its contents total {}", dir.path(), dir.size())));
+    }
+
+    Ok(())
+}
+
+/// Points at the line and column of `json` that `err` was raised for
+fn parse_error(json: &str, err: &serde_json::Error) -> Error {
+    let message = err.to_string();
+    let message = message.split(" at line ").next().unwrap_or(&message);
+    let expected = format!("valid tree JSON: {message}");
+
+    let text = json.lines().nth(err.line().saturating_sub(1)).unwrap_or("");
+    let start = err.column().saturating_sub(1).min(text.len());
+    let end = (start + 1).min(text.len());
+    let parse_error = ParseError::new(text, start..end, expected);
+
+    match err.line() {
+        0 => Error::Parse(parse_error),
+        line => Error::Parse(parse_error.at_line(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use aoc_common::Error;
+    use crate::tests::EXAMPLE;
+    use crate::{Parameters, Results, Tree, TreeOptions};
+
+    #[test]
+    fn round_trip_results() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let json = tree.to_json().unwrap();
+        let imported = Tree::from_json(&json).unwrap();
+        let results = Results::from_tree(&imported, &Parameters::default()).unwrap();
+
+        assert_eq!((results.sum_1, results.size_2), (95_437, 24_933_642));
+        assert_eq!(imported.to_json().unwrap(), json)
+    }
+
+    #[test]
+    fn round_trip_serde() {
+        let tree = Tree::from_input(EXAMPLE, &TreeOptions::default()).unwrap();
+        let value = serde_json::to_value(&tree).unwrap();
+        let imported = serde_json::from_value::<Tree>(value).unwrap();
+
+        assert_eq!(imported.resolve("/a/e/i").map(|node| node.size()), Some(584))
+    }
+
+    #[test]
+    fn round_trip_deep_tree() {
+        // well past serde_json's limit of 128 levels, on a stack this small that recursing once per level overflows it
+        let (json, imported) = thread::Builder::new().stack_size(128 * 1024).spawn(|| {
+            let transcript = format!("{}$ ls\n1 f\n", "$ ls\ndir d\n$ cd d\n".repeat(1_000));
+            let tree = Tree::from_input(&transcript, &TreeOptions::default()).unwrap();
+            let json = tree.to_json().unwrap();
+            let imported = Tree::from_json(&json).unwrap();
+            (json, imported.to_json().unwrap())
+        }).unwrap().join().unwrap();
+
+        assert_eq!(imported, json)
+    }
+
+    #[test]
+    fn nested_json() {
+        let tree = Tree::from_input("$ cd /\n$ ls\ndir a\n10 b\n$ cd a\n$ ls\n5 c\n", &TreeOptions::default()).unwrap();
+
+        assert_eq!(tree.to_json().unwrap(), r#"{
+  "type": "dir",
+  "name": "/",
+  "size": 15,
+  "children": [
+    {
+      "type": "dir",
+      "name": "a",
+      "size": 5,
+      "children": [
+        {
+          "type": "file",
+          "name": "c",
+          "size": 5
+        }
+      ]
+    },
+    {
+      "type": "file",
+      "name": "b",
+      "size": 10
+    }
+  ]
+}"#)
+    }
+
+    #[test]
+    fn import_without_directory_sizes() {
+        let json = r#"{"type": "dir", "name": "/", "children": [{"type": "dir", "name": "a", "children": [{"type": "file", "name": "x", "size": 7}]}]}"#;
+        let tree = Tree::from_json(json).unwrap();
+
+        assert_eq!((tree.total_size(), tree.resolve("/a").map(|node| node.size())), (7, Some(7)))
+    }
+
+    #[test]
+    fn import_wrong_directory_size() {
+        let json = r#"{"type": "dir", "name": "/", "size": 8, "children": [{"type": "file", "name": "x", "size": 7}]}"#;
+        let err = Tree::from_json(json).unwrap_err();
+
+        assert_eq!(err.to_string(), "failed to solve: / has size 8 but its contents total 7")
+    }
+
+    #[test]
+    fn import_conflicting_entries() {
+        let json = r#"{"type": "dir", "name": "/", "children": [{"type": "file", "name": "x", "size": 7}, {"type": "file", "name": "x", "size": 8}]}"#;
+
+        assert!(Tree::from_json(json).is_err())
+    }
+
+    #[test]
+    fn import_invalid_name() {
+        let json = r#"{"type": "dir", "name": "/", "children": [{"type": "file", "name": "a/b", "size": 7}]}"#;
+        let err = Tree::from_json(json).unwrap_err();
+
+        assert_eq!(err.to_string(), "failed to solve: invalid file or directory name: `a/b`")
+    }
+
+    #[test]
+    fn import_root_file() {
+        assert!(Tree::from_json(r#"{"type": "file", "name": "/", "size": 7}"#).is_err())
+    }
+
+    #[test]
+    fn import_malformed_json() {
+        let err = match Tree::from_json("{\n  \"type\": \"dir\",\n  \"name\": /\n}") {
+            Err(Error::Parse(err)) => err,
+            other => panic!("expected parse error, got {other:?}")
+        };
+
+        assert_eq!((err.line, err.found()), (Some(3), "/"))
+    }
+}
@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: aoc run --day <DAY> [--part <PART>] [--input <PATH>]
+       aoc run --all
+       aoc verify [--manifest <PATH>]
+       aoc list
+
+Options:
+    -d, --day <DAY>         day to solve
+    -p, --part <PART>       part to solve, 1 or 2 (default: both)
+    -i, --input <PATH>      puzzle input, or - to read stdin (default: the day's input.txt)
+    -a, --all               solve every day from its input.txt
+    -m, --manifest <PATH>   expected answers to verify (default: answers.txt)
+    -h, --help              print this help
+";
+
+pub enum Command {
+    Run(RunOptions),
+    Verify { manifest: Option<PathBuf> },
+    List,
+    Help
+}
+
+pub enum Days {
+    All,
+    One(u8)
+}
+
+pub enum Input {
+    Default,
+    Stdin,
+    File(PathBuf)
+}
+
+pub struct RunOptions {
+    pub days: Days,
+    pub part: Option<u8>,
+    pub input: Input
+}
+
+/// Parses the command line `args`, excluding the program name
+pub fn parse<I>(args: I) -> Result<Command, String>
+where I: IntoIterator<Item = String> {
+    let mut args = args.into_iter();
+    let command = match args.next() {
+        Some(command) => command,
+        None => return Ok(Command::Help)
+    };
+
+    match command.as_str() {
+        "run" => parse_run(args).map(Command::Run),
+        "verify" => parse_verify(args),
+        "list" => Ok(Command::List),
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        _ => Err(format!("unknown command: {command}"))
+    }
+}
+
+fn parse_run<I>(mut args: I) -> Result<RunOptions, String>
+where I: Iterator<Item = String> {
+    let mut all = false;
+    let mut day = None;
+    let mut part = None;
+    let mut input = Input::Default;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for {arg}"));
+        match arg.as_str() {
+            "-a" | "--all" => all = true,
+            "-d" | "--day" => day = Some(parse_number(&value()?, "day")?),
+            "-p" | "--part" => part = match parse_number(&value()?, "part")? {
+                p @ (1 | 2) => Some(p),
+                p => return Err(format!("invalid part: {p}"))
+            },
+            "-i" | "--input" => input = match value()?.as_str() {
+                "-" => Input::Stdin,
+                path => Input::File(PathBuf::from(path))
+            },
+            _ => return Err(format!("unknown option: {arg}"))
+        }
+    }
+
+    let days = if all {
+        match day {
+            Some(_) => return Err(String::from("--all cannot be combined with --day")),
+            None => Days::All
+        }
+    } else {
+        match day {
+            Some(day) => Days::One(day),
+            None => return Err(String::from("either --day or --all is required"))
+        }
+    };
+
+    if let (Days::All, Input::File(_) | Input::Stdin) = (&days, &input) {
+        return Err(String::from("--all cannot be combined with --input"));
+    }
+
+    Ok(RunOptions { days, part, input })
+}
+
+fn parse_verify<I>(mut args: I) -> Result<Command, String>
+where I: Iterator<Item = String> {
+    let mut manifest = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-m" | "--manifest" => manifest = Some(PathBuf::from(args.next().ok_or_else(|| format!("missing value for {arg}"))?)),
+            _ => return Err(format!("unknown option: {arg}"))
+        }
+    }
+
+    Ok(Command::Verify { manifest })
+}
+
+fn parse_number(value: &str, name: &str) -> Result<u8, String> {
+    value.parse::<u8>().map_err(|_| format!("invalid {name}: {value}"))
+}
@@ -0,0 +1,133 @@
+//! Runs the Advent of Code 2022 solutions of every day through one entry point
+
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use aoc_common::{Error, Result, Solution};
+
+pub mod verify;
+
+/// Answers to both parts of a day's puzzle, formatted for display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answers {
+    pub part_1: String,
+    pub part_2: String
+}
+
+impl Answers {
+    pub fn of<S>(solution: &S) -> Answers
+    where S: Solution {
+        Answers {
+            part_1: solution.part_1().to_string(),
+            part_2: solution.part_2().to_string()
+        }
+    }
+
+    /// Gets the answer to `part`, which must be 1 or 2
+    pub fn part(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => Some(&self.part_1),
+            2 => Some(&self.part_2),
+            _ => None
+        }
+    }
+}
+
+/// A day with a Rust solution
+pub struct Day {
+    pub day: u8,
+    pub title: &'static str,
+    /// Directory containing the day's `input.txt`, relative to the repository root
+    pub dir: &'static str,
+    run: fn(&Path) -> Result<Answers>,
+    run_lines: fn(&str) -> Result<Answers>,
+    run_reader: fn(&mut dyn BufRead) -> Result<Answers>
+}
+
+impl Day {
+    /// Solves the day from a file path `filename`
+    pub fn run(&self, filename: &Path) -> Result<Answers> {
+        (self.run)(filename)
+    }
+
+    /// Solves the day from a string slice `input`
+    pub fn run_lines(&self, input: &str) -> Result<Answers> {
+        (self.run_lines)(input)
+    }
+
+    /// Solves the day from a buffered `reader` as it reads, e.g. stdin
+    pub fn run_reader(&self, reader: &mut dyn BufRead) -> Result<Answers> {
+        (self.run_reader)(reader)
+    }
+
+    /// Path of the day's puzzle input
+    pub fn input_path(&self) -> PathBuf {
+        repository_root().join(self.dir).join("input.txt")
+    }
+}
+
+macro_rules! day {
+    ($krate:ident, $results:ident, $dir:literal) => {
+        Day {
+            day: <$krate::$results as Solution>::DAY,
+            title: <$krate::$results as Solution>::TITLE,
+            dir: $dir,
+            run: |filename| $krate::run(filename).map(|results| Answers::of(&results)),
+            run_lines: |input| $krate::run_lines(input).map(|results| Answers::of(&results)),
+            run_reader: |reader| <$krate::$results as Solution>::from_reader(reader).map(|results| Answers::of(&results))
+        }
+    };
+}
+
+/// Every day with a Rust solution, in order
+pub const DAYS: &[Day] = &[
+    day!(day_01_calorie_counting, TopCalories, "day-01-calorie-counting"),
+    day!(day_02_rock_paper_scissors, Results, "day-02-rock-paper-scissors"),
+    day!(day_03_rucksack_reorganization, Results, "day-03-rucksack-reorganization"),
+    day!(day_04_camp_cleanup, Results, "day-04-camp-cleanup"),
+    day!(day_06_tuning_trouble, Results, "day-06-tuning-trouble"),
+    day!(day_07_no_space_left, Results, "day-07-no-space-left")
+];
+
+/// Finds the solution for `day`
+pub fn find_day(day: u8) -> Result<&'static Day> {
+    DAYS.iter()
+        .find(|d| d.day == day)
+        .ok_or_else(|| Error::solve(format!("day {day} has no Rust solution")))
+}
+
+/// Root of the repository, which holds one directory per day
+pub fn repository_root() -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    manifest_dir.ancestors().nth(2).unwrap_or(manifest_dir).to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{find_day, DAYS};
+
+    #[test]
+    fn days_are_in_order() {
+        let days = DAYS.iter().map(|d| d.day).collect::<Vec<_>>();
+
+        assert_eq!(days, vec![1, 2, 3, 4, 6, 7])
+    }
+
+    #[test]
+    fn find_missing_day() {
+        assert!(find_day(5).is_err())
+    }
+
+    #[test]
+    fn run_lines_day_4() {
+        let answers = find_day(4).unwrap().run_lines("2-4,6-8\n2-8,3-7\n").unwrap();
+
+        assert_eq!((answers.part(1), answers.part(2)), (Some("1"), Some("1")))
+    }
+
+    #[test]
+    fn run_reader_day_4() {
+        let answers = find_day(4).unwrap().run_reader(&mut "2-4,6-8\n2-8,3-7\n".as_bytes()).unwrap();
+
+        assert_eq!((answers.part(1), answers.part(2)), (Some("1"), Some("1")))
+    }
+}
@@ -0,0 +1,108 @@
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process::ExitCode;
+use aoc::verify::{verify, Manifest, Summary};
+use aoc::{find_day, Answers, Day, DAYS};
+use aoc_common::Result;
+use cli::{Command, Days, Input, RunOptions};
+
+mod cli;
+
+fn main() -> ExitCode {
+    let command = match cli::parse(env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Run(options) => run(&options),
+        Command::Verify { manifest } => match manifest {
+            Some(path) => run_verify(&path),
+            None => run_verify(&Manifest::default_path())
+        },
+        Command::List => {
+            for day in DAYS {
+                println!("day {}: {}", day.day, day.title);
+            }
+            ExitCode::SUCCESS
+        },
+        Command::Help => {
+            print!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn run(options: &RunOptions) -> ExitCode {
+    let days = match options.days {
+        Days::All => DAYS.iter().collect::<Vec<_>>(),
+        Days::One(day) => match find_day(day) {
+            Ok(day) => vec![day],
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+
+    let mut exit_code = ExitCode::SUCCESS;
+    for day in days {
+        match solve(day, &options.input) {
+            Ok(answers) => print_answers(day, &answers, options.part),
+            Err(err) => {
+                eprintln!("error: day {}: {err}", day.day);
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    exit_code
+}
+
+fn run_verify(manifest_path: &Path) -> ExitCode {
+    let manifest = match Manifest::load(manifest_path) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            eprintln!("error: {}: {err}", manifest_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let outcomes = verify(&manifest);
+    for outcome in &outcomes {
+        println!("{outcome}");
+    }
+
+    let summary = Summary::of(&outcomes);
+    println!("{summary}");
+    if summary.is_success() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn solve(day: &Day, input: &Input) -> Result<Answers> {
+    match input {
+        Input::Default => day.run(&day.input_path()),
+        Input::File(path) => day.run(path),
+        Input::Stdin => day.run_reader(&mut io::stdin().lock())
+    }
+}
+
+fn print_answers(day: &Day, answers: &Answers, part: Option<u8>) {
+    let parts = match part {
+        Some(part) => vec![part],
+        None => vec![1, 2]
+    };
+
+    for part in parts {
+        if let Some(answer) = answers.part(part) {
+            println!("day {} part {part}: {answer}", day.day);
+        }
+    }
+}
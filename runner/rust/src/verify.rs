@@ -0,0 +1,258 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use aoc_common::{ParseError, Result};
+use crate::{find_day, repository_root, Answers, DAYS};
+
+/// An expected answer for one part of a day, solved from `input`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub day: u8,
+    pub part: u8,
+    pub input: PathBuf,
+    pub answer: String
+}
+
+/// Expected answers read from a manifest file
+///
+/// Each non-empty line that doesn't start with `#` holds `<day> <part> <input> <answer>`,
+/// where `input` is relative to the manifest's directory.
+#[derive(Debug, Default)]
+pub struct Manifest {
+    pub entries: Vec<Expected>
+}
+
+impl Manifest {
+    /// Path of the manifest checked into the repository
+    pub fn default_path() -> PathBuf {
+        repository_root().join("answers.txt")
+    }
+
+    /// Loads the manifest at `filename`
+    pub fn load<P>(filename: P) -> Result<Manifest>
+    where P: AsRef<Path> {
+        let filename = filename.as_ref();
+        let contents = fs::read_to_string(filename)?;
+        let base = filename.parent().unwrap_or_else(|| Path::new(""));
+
+        Manifest::parse(&contents, base)
+    }
+
+    /// Parses manifest `contents`, resolving inputs relative to `base`
+    pub fn parse(contents: &str, base: &Path) -> Result<Manifest> {
+        let mut entries = Vec::new();
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let [day, part, input, answer] = fields[..] else {
+                return Err(ParseError::at(line, line, "`<day> <part> <input> <answer>`").at_line(i + 1).into());
+            };
+
+            let parse_number = |value: &str| value.parse::<u8>()
+                .map_err(|_| ParseError::at(line, value, "number").at_line(i + 1));
+
+            entries.push(Expected {
+                day: parse_number(day)?,
+                part: parse_number(part)?,
+                input: base.join(input),
+                answer: answer.to_string()
+            });
+        }
+
+        Ok(Manifest { entries })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail { actual: String },
+    /// The day couldn't be solved from the input
+    Error(String),
+    /// There is no expected answer, or no input to check it against
+    Missing(String)
+}
+
+/// The result of checking one part of a day
+#[derive(Debug)]
+pub struct Outcome {
+    pub day: u8,
+    pub part: u8,
+    pub input: Option<PathBuf>,
+    pub status: Status,
+    /// Time spent solving both parts from the input
+    pub elapsed: Option<Duration>
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let label = match self.status {
+            Status::Pass => "PASS",
+            Status::Fail { .. } => "FAIL",
+            Status::Error(_) => "ERROR",
+            Status::Missing(_) => "MISSING"
+        };
+
+        write!(f, "{label:<7} day {} part {}", self.day, self.part)?;
+        if let Some(input) = &self.input {
+            write!(f, " ({})", input.display())?;
+        }
+
+        if let Some(elapsed) = self.elapsed {
+            write!(f, " in {:.3} ms", elapsed.as_secs_f64() * 1000.0)?;
+        }
+
+        match &self.status {
+            Status::Pass => Ok(()),
+            Status::Fail { actual } => write!(f, ": got {actual}"),
+            Status::Error(details) | Status::Missing(details) => write!(f, ": {details}")
+        }
+    }
+}
+
+/// Totals over a list of outcomes
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub missing: usize
+}
+
+impl Summary {
+    pub fn of(outcomes: &[Outcome]) -> Summary {
+        let mut summary = Summary::default();
+        for outcome in outcomes {
+            match outcome.status {
+                Status::Pass => summary.passed += 1,
+                Status::Fail { .. } => summary.failed += 1,
+                Status::Error(_) => summary.errors += 1,
+                Status::Missing(_) => summary.missing += 1
+            }
+        }
+
+        summary
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failed == 0 && self.errors == 0
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} passed, {} failed, {} errors, {} missing", self.passed, self.failed, self.errors, self.missing)
+    }
+}
+
+/// Solves every day in `manifest` against its input and compares the answers,
+/// reporting parts of days without an expected answer as missing
+pub fn verify(manifest: &Manifest) -> Vec<Outcome> {
+    let mut outcomes = Vec::new();
+    let mut solved: Vec<(u8, &Path, std::result::Result<Answers, String>, Duration)> = Vec::new();
+
+    for expected in &manifest.entries {
+        let input = expected.input.as_path();
+        let cached = solved.iter().position(|(day, path, _, _)| *day == expected.day && *path == input);
+        let index = match cached {
+            Some(index) => index,
+            None => {
+                let day = match find_day(expected.day) {
+                    Ok(day) => day,
+                    Err(err) => {
+                        outcomes.push(outcome(expected, Status::Missing(err.to_string()), None));
+                        continue;
+                    }
+                };
+
+                if !input.is_file() {
+                    outcomes.push(outcome(expected, Status::Missing(String::from("input not found")), None));
+                    continue;
+                }
+
+                let start = Instant::now();
+                let answers = day.run(input).map_err(|err| err.to_string());
+                solved.push((expected.day, input, answers, start.elapsed()));
+                solved.len() - 1
+            }
+        };
+
+        let (_, _, answers, elapsed) = &solved[index];
+        let status = match answers {
+            Ok(answers) => match answers.part(expected.part) {
+                Some(actual) if actual == expected.answer => Status::Pass,
+                Some(actual) => Status::Fail { actual: actual.to_string() },
+                None => Status::Missing(format!("day {} has no part {}", expected.day, expected.part))
+            },
+            Err(err) => Status::Error(err.clone())
+        };
+        outcomes.push(outcome(expected, status, Some(*elapsed)));
+    }
+
+    for day in DAYS {
+        for part in [1, 2] {
+            if !manifest.entries.iter().any(|e| e.day == day.day && e.part == part) {
+                outcomes.push(Outcome {
+                    day: day.day,
+                    part,
+                    input: None,
+                    status: Status::Missing(String::from("no expected answer")),
+                    elapsed: None
+                });
+            }
+        }
+    }
+
+    outcomes
+}
+
+fn outcome(expected: &Expected, status: Status, elapsed: Option<Duration>) -> Outcome {
+    Outcome {
+        day: expected.day,
+        part: expected.part,
+        input: Some(expected.input.clone()),
+        status,
+        elapsed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::verify::{verify, Manifest, Status, Summary};
+
+    #[test]
+    fn parse_manifest() {
+        let manifest = Manifest::parse("# comment\n\n4 2 day-04/input.txt 845\n", Path::new("root")).unwrap();
+
+        assert_eq!(manifest.entries.len(), 1);
+        assert_eq!(manifest.entries[0].input, Path::new("root/day-04/input.txt"));
+        assert_eq!(manifest.entries[0].answer, "845")
+    }
+
+    #[test]
+    fn parse_manifest_missing_field() {
+        assert!(Manifest::parse("4 2 day-04/input.txt\n", Path::new("")).is_err())
+    }
+
+    #[test]
+    fn verify_reports_fail_and_missing() {
+        let contents = "\
+1 1 day-01-calorie-counting/input.txt 0
+1 2 day-01-calorie-counting/missing.txt 0
+5 1 day-05-supply-stacks/input.txt CMZ
+";
+        let manifest = Manifest::parse(contents, &crate::repository_root()).unwrap();
+        let outcomes = verify(&manifest);
+
+        assert_eq!(outcomes[0].status, Status::Fail { actual: String::from("69289") });
+        assert!(matches!(outcomes[1].status, Status::Missing(_)));
+        assert!(matches!(outcomes[2].status, Status::Missing(_)));
+        assert_eq!(Summary::of(&outcomes), Summary { passed: 0, failed: 1, errors: 0, missing: 12 })
+    }
+}
@@ -0,0 +1,31 @@
+use aoc::verify::{verify, Manifest, Status, Summary};
+
+#[test]
+fn answers_match_manifest() {
+    let manifest = Manifest::load(Manifest::default_path()).unwrap();
+    let outcomes = verify(&manifest);
+    for outcome in &outcomes {
+        println!("{outcome}");
+    }
+
+    let failures = outcomes.iter()
+        .filter(|o| matches!(o.status, Status::Fail { .. } | Status::Error(_)))
+        .map(|o| o.to_string())
+        .collect::<Vec<_>>();
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+    assert!(Summary::of(&outcomes).is_success())
+}
+
+#[test]
+fn every_day_has_expected_answers() {
+    let manifest = Manifest::load(Manifest::default_path()).unwrap();
+    let outcomes = verify(&manifest);
+
+    let missing = outcomes.iter()
+        .filter(|o| matches!(o.status, Status::Missing(_)))
+        .map(|o| o.to_string())
+        .collect::<Vec<_>>();
+
+    assert!(missing.is_empty(), "{}", missing.join("\n"))
+}